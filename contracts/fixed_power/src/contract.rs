@@ -1,13 +1,13 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{
-    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response, StdResult,
-    WasmQuery,
-};
+use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 // use cw2::set_contract_version;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GetVotingPowerMsg, GetVotingPowerResponse, InstantiateMsg};
+use crate::msg::{
+    ExecuteMsg, GetTotalPowerResponse, GetVotingPowerResponse, InstantiateMsg, QueryMsg,
+};
+use crate::state::TOTAL_POWER;
 
 /*
 // version info for migration info
@@ -17,11 +17,13 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
-    _msg: InstantiateMsg,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    TOTAL_POWER.save(deps.storage, &msg.total_power)?;
+
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
@@ -35,8 +37,16 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, msg: GetVotingPowerMsg) -> StdResult<Binary> {
-    to_json_binary(&GetVotingPowerResponse { power: 1 })
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        // the power is fixed, so it is the same at any point in time
+        QueryMsg::GetVotingPowerMsg { voter: _, time: _ } => {
+            to_json_binary(&GetVotingPowerResponse { power: 1 })
+        }
+        QueryMsg::GetTotalPowerMsg { time: _ } => to_json_binary(&GetTotalPowerResponse {
+            power: TOTAL_POWER.load(deps.storage)?,
+        }),
+    }
 }
 
 #[cfg(test)]
@@ -2,5 +2,6 @@ pub mod contract;
 mod error;
 pub mod helpers;
 pub mod msg;
+pub mod state;
 
 pub use crate::error::ContractError;
@@ -1,7 +1,11 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Timestamp;
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    // The total voting power that is reported to governance.
+    pub total_power: u64,
+}
 
 #[cw_serde]
 pub struct ExecuteMsg {}
@@ -10,16 +14,28 @@ pub struct ExecuteMsg {}
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(GetVotingPowerResponse)]
-    GetVotingPowerMsg { voter: String },
+    GetVotingPowerMsg {
+        voter: String,
+        time: Option<Timestamp>,
+    },
+
+    #[returns(GetTotalPowerResponse)]
+    GetTotalPowerMsg { time: Option<Timestamp> },
 }
 
 // The message that needs to be sent to the power contract to get the voting power of a voter.
 #[cw_serde]
 pub struct GetVotingPowerMsg {
     pub voter: String,
+    pub time: Option<Timestamp>,
 }
 
 #[cw_serde]
 pub struct GetVotingPowerResponse {
     pub power: u64,
 }
+
+#[cw_serde]
+pub struct GetTotalPowerResponse {
+    pub power: u64,
+}
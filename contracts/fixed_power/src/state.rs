@@ -0,0 +1,4 @@
+use cw_storage_plus::Item;
+
+// The total voting power, as given at instantiation.
+pub const TOTAL_POWER: Item<u64> = Item::new("total_power");
@@ -1,40 +1,66 @@
 use std::vec;
 
-use abstract_app::objects::module::ModuleInfo;
 use abstract_app::sdk::{AbstractResponse, IbcInterface};
 use abstract_app::std::ibc::{CallbackInfo, CallbackResult, IbcResponseMsg, ModuleIbcMsg};
+use abstract_app::std::objects::module::ModuleInfo;
 
-use abstract_app::std::ibc_client;
-use abstract_client::Namespace;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_json, to_json_binary, Binary, Deps, DepsMut, Env, Event, MessageInfo, QueryRequest,
-    Response, StdResult, WasmQuery,
+    from_json, to_json_binary, wasm_execute, BankMsg, Binary, Coins, CosmosMsg, Decimal, Deps,
+    DepsMut, Empty, Env, Event, MessageInfo, Order, QueryRequest, Reply, Response, StdResult,
+    Storage, SubMsg, Timestamp, Uint128, WasmQuery,
 };
-use cw_multi_test::Contract;
+use cw_storage_plus::Bound;
+use cw_utils::Expiration;
 // use cw2::set_contract_version;
 
-use abstract_app::objects::module::ModuleVersion::Version;
-
 use crate::error::ContractError;
 use crate::msg::{
     CCGovExecuteMsg, CCGovIbcMessage, CCGovInstantiateMsg, CCGovMigrateMsg, CCGovQueryMsg,
-    GetVotingPowerMsg, GetVotingPowerResponse, QueryExecutedProposalsResponse, QueryMsg,
-    QueryProposalResponse, QueryTallyResponse, QueryTotalVotedPowerResponse, QueryVoteResponse,
-    RemoteProposalMsg,
+    ConfigResponse, DelegatedPowerResponse, DelegationResponse, ExecuteMsg, GetTotalPowerMsg,
+    GetTotalPowerResponse, GetVotingPowerMsg, GetVotingPowerResponse, ListDelegatorsResponse,
+    ListPowerContractsResponse, ListProposalsResponse, ListRemotePowerContractsResponse,
+    ListTrustedInstancesResponse, ListTrustedModulesResponse, ListVotesResponse,
+    PowerContractQueryMsg, PrerequisiteStatus, QueryExecutedProposalsResponse, QueryMsg,
+    QueryPrerequisitesResponse, QueryProposalResponse, QueryTallyResponse,
+    QueryTotalVotedPowerResponse, QueryVoteResponse, RemoteProposalMsg, RemoteTotalPowerMsg,
+    RemoteVoteMsg, SourceTally,
 };
 use crate::state::{
-    Proposal, Vote, EXECUTED_PROPOSALS, PROP_ID, PROP_MAP, REMOTE_PROPOSALS,
-    REMOTE_PROPOSALS_TALLIES, REMOTE_PROPOSAL_ID, REMOTE_PROPOSAL_RESOLVED, VOTE_ID, VOTE_MAP,
-    VOTING_PERIOD_IN_MINUTES,
+    AggregationStrategy, Config, Normalization, OptionKind, PrerequisiteProposal,
+    PrerequisiteValidation, Proposal, ProposalStatus, RemoteVotingChain, Vote, CONFIG,
+    DELEGATED_VOTES, DELEGATIONS, DELEGATORS, DEPOSITS, DEPOSIT_NOT_REACHED_OUTCOME,
+    EXECUTED_PROPOSALS, FAILED_QUORUM_OUTCOME, POWER_CONTRACT_WHITELIST, PROP_ID, PROP_MAP,
+    REMOTE_POWER_CONTRACT_WHITELIST, REMOTE_PROPOSALS, REMOTE_PROPOSALS_TALLIES,
+    REMOTE_PROPOSALS_TOTAL_POWER, REMOTE_PROPOSAL_ID, REMOTE_PROPOSAL_OUTCOMES,
+    REMOTE_PROPOSAL_RESOLVED, REMOTE_PROPOSAL_VALIDATIONS, REMOTE_VOTES, REMOTE_VOTING_TOTAL_POWER,
+    SUBSCRIBERS, THRESHOLD_NOT_REACHED_OUTCOME, TRUSTED_INSTANCES, TRUSTED_MODULES, VETOED_OUTCOME,
+    VOTES_BY_VOTER, VOTE_ID, VOTE_MAP,
+};
+use crate::{
+    APP_VERSION, AUTO_EXECUTE_REPLY_ID, CCGOV_ID, QUERY_TALLY_CALLBACK_ID,
+    REMOTE_TOTAL_POWER_CALLBACK_ID, REMOTE_VOTE_CALLBACK_ID, VALIDATE_PREREQ_CALLBACK_ID,
 };
-use crate::{APP_VERSION, CCGOV_ID, CCGOV_NAMESPACE, QUERY_TALLY_CALLBACK_ID};
 
 use abstract_app::AppContract;
 
 pub type CCGovResult<T = Response> = Result<T, ContractError>;
 
+// default and maximum number of items returned by list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// maximum number of delegators per delegate, as a vote queries the power of each of them
+pub const MAX_DELEGATORS: usize = 50;
+
+// maximum number of subscribers per proposal, as its execution sends the result to each of them
+pub const MAX_SUBSCRIBERS: usize = 20;
+
+// maximum factor of a Multiplier normalization, so that a typo in the factor cannot make the
+// tally of a proposal overflow
+pub const MAX_MULTIPLIER: u64 = 1_000_000;
+
 pub type CCGovApp = AppContract<
     ContractError,
     CCGovInstantiateMsg,
@@ -49,7 +75,13 @@ const APP: CCGovApp = CCGovApp::new(CCGOV_ID, APP_VERSION, None)
     .with_dependencies(&[])
     .with_instantiate(instantiate_handler)
     .with_module_ibc(module_ibc_handler)
-    .with_ibc_callbacks(&[(QUERY_TALLY_CALLBACK_ID, query_tally_callback)]);
+    .with_ibc_callbacks(&[
+        (QUERY_TALLY_CALLBACK_ID, query_tally_callback),
+        (REMOTE_VOTE_CALLBACK_ID, remote_vote_callback),
+        (REMOTE_TOTAL_POWER_CALLBACK_ID, remote_total_power_callback),
+        (VALIDATE_PREREQ_CALLBACK_ID, validate_prereq_callback),
+    ])
+    .with_replies(&[(AUTO_EXECUTE_REPLY_ID, auto_execute_reply)]);
 
 #[cfg(not(target_arch = "wasm32"))]
 impl<Chain: cw_orch::environment::CwEnv> abstract_interface::DependencyCreation
@@ -72,26 +104,87 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate_handler(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     _app: CCGovApp,
-    _msg: CCGovInstantiateMsg,
+    msg: CCGovInstantiateMsg,
 ) -> Result<Response, ContractError> {
-    PROP_ID.save(_deps.storage, &0)?;
-    VOTE_ID.save(_deps.storage, &0)?;
-    REMOTE_PROPOSAL_ID.save(_deps.storage, &0)?;
+    PROP_ID.save(deps.storage, &0)?;
+    VOTE_ID.save(deps.storage, &0)?;
+    REMOTE_PROPOSAL_ID.save(deps.storage, &0)?;
 
-    VOTING_PERIOD_IN_MINUTES.save(_deps.storage, &1)?;
+    let config = validated_config(deps.as_ref(), msg)?;
+    CONFIG.save(deps.storage, &config)?;
 
-    // set the executed proposals to an empty list
-    EXECUTED_PROPOSALS.save(_deps.storage, &Vec::new())?;
+    // the default power contract can be used right away
+    if let Some(default_power_contract) = config.default_power_contract {
+        POWER_CONTRACT_WHITELIST.save(deps.storage, default_power_contract, &Empty {})?;
+    }
 
     Ok(Response::new()
         .add_attribute("action", "initialisation")
         .add_attribute("sender", info.sender.clone()))
 }
 
+// Checks the parameters of an instantiate or UpdateConfig message and turns them into a config.
+fn validated_config(deps: Deps, msg: CCGovInstantiateMsg) -> Result<Config, ContractError> {
+    if msg.min_voting_period_in_minutes == 0 {
+        return Err(ContractError::InvalidConfig {
+            reason: "voting period must be positive".to_string(),
+        });
+    }
+    if msg.voting_period_in_minutes < msg.min_voting_period_in_minutes
+        || msg.voting_period_in_minutes > msg.max_voting_period_in_minutes
+    {
+        return Err(ContractError::InvalidConfig {
+            reason: "voting period must be between the min and max voting period".to_string(),
+        });
+    }
+    if msg.quorum > Decimal::one()
+        || msg.threshold > Decimal::one()
+        || msg.veto_threshold > Decimal::one()
+    {
+        return Err(ContractError::InvalidConfig {
+            reason: "quorum, threshold and veto threshold must be at most 1".to_string(),
+        });
+    }
+
+    let min_deposit = Coins::try_from(msg.min_deposit)
+        .map_err(|_| ContractError::InvalidConfig {
+            reason: "min deposit must not contain duplicate denoms".to_string(),
+        })?
+        .into_vec();
+
+    Ok(Config {
+        voting_period_in_minutes: msg.voting_period_in_minutes,
+        min_voting_period_in_minutes: msg.min_voting_period_in_minutes,
+        max_voting_period_in_minutes: msg.max_voting_period_in_minutes,
+        default_power_contract: msg
+            .default_power_contract
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?
+            .map(String::from),
+        quorum: msg.quorum,
+        threshold: msg.threshold,
+        veto_threshold: msg.veto_threshold,
+        allow_revoting: msg.allow_revoting,
+        auto_execute: msg.auto_execute,
+        min_deposit,
+        deposit_period_in_minutes: msg.deposit_period_in_minutes,
+        deposit_recipient: msg
+            .deposit_recipient
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?
+            .map(String::from),
+        admin: msg
+            .admin
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?
+            .map(String::from),
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute_handler(
     deps: DepsMut,
@@ -107,203 +200,1067 @@ pub fn execute_handler(
             power_contract_addr,
             options,
             prereq_proposals,
+            voting_period_in_minutes,
+            start_time,
+            expiration,
+            aggregation_strategy,
+            remote_voting_chains,
+            validate_prereqs,
         } => {
+            let config = CONFIG.load(deps.storage)?;
+
+            // voting can be scheduled to start in the future, but not in the past
+            let start_time = start_time.unwrap_or(env.block.time);
+            if start_time < env.block.time {
+                return Err(ContractError::InvalidStartTime {});
+            }
+
+            // voting either ends after a voting period or at an explicit expiration
+            let (voting_period_in_minutes, expiration) =
+                match (voting_period_in_minutes, expiration) {
+                    (Some(_), Some(_)) => return Err(ContractError::InvalidVotingPeriod {}),
+                    (None, Some(expiration)) => {
+                        assert_valid_expiration(&config, &env, start_time, &expiration)?;
+                        (None, expiration)
+                    }
+                    (voting_period_in_minutes, None) => {
+                        // the voting period needs to be within the bounds from the config
+                        let voting_period_in_minutes =
+                            voting_period_in_minutes.unwrap_or(config.voting_period_in_minutes);
+                        if voting_period_in_minutes < config.min_voting_period_in_minutes
+                            || voting_period_in_minutes > config.max_voting_period_in_minutes
+                        {
+                            return Err(ContractError::InvalidVotingPeriod {});
+                        }
+                        (
+                            Some(voting_period_in_minutes),
+                            Expiration::AtTime(start_time.plus_minutes(voting_period_in_minutes)),
+                        )
+                    }
+                };
+
+            let power_contract_addr = match power_contract_addr {
+                Some(addr) => addr,
+                None => config
+                    .default_power_contract
+                    .clone()
+                    .ok_or(ContractError::NoPowerContract {})?,
+            };
+
+            // check that the power contract was whitelisted
+            if !POWER_CONTRACT_WHITELIST.has(deps.storage, power_contract_addr.clone()) {
+                return Err(ContractError::PowerContractNotWhitelisted {});
+            }
+
+            // the power contracts of remote voting chains must be whitelisted as well
+            for remote_chain in remote_voting_chains.iter().flatten() {
+                if !REMOTE_POWER_CONTRACT_WHITELIST.has(
+                    deps.storage,
+                    (
+                        remote_chain.chain.clone(),
+                        remote_chain.power_contract.clone(),
+                    ),
+                ) {
+                    return Err(ContractError::PowerContractNotWhitelisted {});
+                }
+            }
+
+            // prerequisites must be proposals of trusted ccgov instances
+            for prereq in prereq_proposals.iter() {
+                if !TRUSTED_INSTANCES.has(
+                    deps.storage,
+                    (prereq.chain.clone(), prereq.contract_addr.clone()),
+                ) {
+                    return Err(ContractError::UntrustedPrerequisite {});
+                }
+            }
+
+            // options cannot be named like the outcomes of proposals without a winning option
+            if options.iter().any(|option| {
+                option.name == FAILED_QUORUM_OUTCOME
+                    || option.name == THRESHOLD_NOT_REACHED_OUTCOME
+                    || option.name == VETOED_OUTCOME
+                    || option.name == DEPOSIT_NOT_REACHED_OUTCOME
+            }) {
+                return Err(ContractError::InvalidOption {});
+            }
+
+            // there can be at most one abstain and one veto option
+            for kind in [OptionKind::Abstain, OptionKind::NoWithVeto] {
+                if options.iter().filter(|option| option.kind == kind).count() > 1 {
+                    return Err(ContractError::InvalidOption {});
+                }
+            }
+
+            assert_valid_aggregation(
+                &prereq_proposals,
+                remote_voting_chains.as_deref().unwrap_or_default(),
+                aggregation_strategy.as_ref(),
+            )?;
+
             let prop_id = PROP_ID.load(deps.storage)?;
 
             PROP_ID.save(deps.storage, &(prop_id + 1))?;
 
             let mut prereq_ids = vec![];
+            let mut subscribe_msgs = vec![];
+            let mut validate_msgs = vec![];
 
             // for each proposal in prereq_proposals, create a remote proposal
-            for (prereq_prop_id, remote_chain, remote_module_addr) in prereq_proposals.iter() {
+            for prereq in prereq_proposals.iter() {
                 let remote_proposal_id = REMOTE_PROPOSAL_ID.load(deps.storage)?;
-                REMOTE_PROPOSALS.save(
-                    deps.storage,
-                    prop_id,
-                    &(
-                        remote_proposal_id,
-                        remote_chain.clone(),
-                        remote_module_addr.clone(),
-                    ),
-                )?;
+                REMOTE_PROPOSALS.save(deps.storage, remote_proposal_id, prereq)?;
                 REMOTE_PROPOSAL_ID.save(deps.storage, &(remote_proposal_id + 1))?;
                 prereq_ids.push(remote_proposal_id);
+
+                // ask the remote chain to send the result of the proposal once it is executed
+                if prereq.subscribe {
+                    subscribe_msgs.push(app.ibc_client(deps.as_ref()).module_ibc_action(
+                        prereq.chain.clone(),
+                        ccgov_module_info()?,
+                        &CCGovIbcMessage::Subscribe {
+                            prop_id: prereq.prop_id,
+                            parent_prop_id: prop_id,
+                            remote_prop_id: remote_proposal_id,
+                            contract_addr: prereq.contract_addr.clone(),
+                        },
+                        None,
+                    )?);
+                }
+
+                // ask the remote chain for the proposal to check that it exists and matches
+                if validate_prereqs.unwrap_or(false) {
+                    let wasm_query = WasmQuery::Smart {
+                        contract_addr: prereq.contract_addr.clone(),
+                        msg: to_json_binary(&QueryMsg::Module(CCGovQueryMsg::QueryProposal {
+                            prop_id: prereq.prop_id,
+                        }))?,
+                    };
+                    let remote_prop_msg = RemoteProposalMsg {
+                        parent_prop_id: prop_id,
+                        prop_id: remote_proposal_id,
+                        remote_chain_id: prereq.chain.clone(),
+                        remote_contract_addr: prereq.contract_addr.clone(),
+                    };
+                    validate_msgs.push(app.ibc_client(deps.as_ref()).ibc_query(
+                        prereq.chain.clone(),
+                        wasm_query,
+                        CallbackInfo::new(
+                            VALIDATE_PREREQ_CALLBACK_ID,
+                            Some(to_json_binary(&remote_prop_msg)?),
+                        ),
+                    )?);
+                }
             }
 
-            let prop = Proposal {
+            let mut prop = Proposal {
                 id: prop_id,
                 title,
                 description,
-                start_time: env.block.time,
-                executed: false,
+                start_time,
+                voting_period_in_minutes,
+                expiration,
+                deposit_end_time: env
+                    .block
+                    .time
+                    .plus_minutes(config.deposit_period_in_minutes),
+                deposit: vec![],
+                proposer: info.sender.to_string(),
+                status: ProposalStatus::DepositPeriod,
                 power_contract: power_contract_addr,
                 options,
                 prereq_proposals: prereq_ids,
+                aggregation_strategy: aggregation_strategy.unwrap_or(AggregationStrategy::Sum),
+                remote_voting_chains: remote_voting_chains.unwrap_or_default(),
             };
+
+            // the attached funds are the initial deposit, voting starts once it is enough
+            add_deposit(deps.storage, &config, &mut prop, &info)?;
+            if deposit_reached(&config, &prop) {
+                prop.status = ProposalStatus::Open;
+            }
             PROP_MAP.save(deps.storage, prop_id, &prop)?;
 
+            // the total power of remote voting chains is queried once voting started
+            let query_msgs =
+                if prop.status == ProposalStatus::Open && prop.start_time == env.block.time {
+                    remote_total_power_msgs(deps.as_ref(), &app, &prop)?
+                } else {
+                    vec![]
+                };
+
             Ok(Response::new()
                 .add_attribute("action", "create_proposal")
-                .add_attribute("prop_id", prop_id.to_string()))
+                .add_attribute("prop_id", prop_id.to_string())
+                .add_messages(subscribe_msgs)
+                .add_messages(validate_msgs)
+                .add_messages(query_msgs))
+        }
+        CCGovExecuteMsg::Deposit { prop_id } => {
+            let mut prop = PROP_MAP.load(deps.storage, prop_id)?;
+            if prop.status != ProposalStatus::DepositPeriod
+                || prop.deposit_end_time <= env.block.time
+            {
+                return Err(ContractError::NotInDepositPeriod {});
+            }
+
+            let config = CONFIG.load(deps.storage)?;
+            add_deposit(deps.storage, &config, &mut prop, &info)?;
+
+            // the voting period starts once the minimum deposit is reached
+            let voting_started = deposit_reached(&config, &prop);
+            let mut query_msgs = vec![];
+            if voting_started {
+                prop.status = ProposalStatus::Open;
+                // a scheduled start is kept if it is still in the future
+                prop.start_time = prop.start_time.max(env.block.time);
+                if let Some(voting_period_in_minutes) = prop.voting_period_in_minutes {
+                    prop.expiration =
+                        Expiration::AtTime(prop.start_time.plus_minutes(voting_period_in_minutes));
+                }
+                // the total power of remote voting chains is queried once voting started
+                if prop.start_time == env.block.time {
+                    query_msgs = remote_total_power_msgs(deps.as_ref(), &app, &prop)?;
+                }
+            }
+            PROP_MAP.save(deps.storage, prop_id, &prop)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "deposit")
+                .add_attribute("prop_id", prop_id.to_string())
+                .add_attribute("voting_started", voting_started.to_string())
+                .add_messages(query_msgs))
         }
         CCGovExecuteMsg::Vote { prop_id, option } => {
-            let vote_id = VOTE_ID.load(deps.storage)?;
-            let prop = PROP_MAP.load(deps.storage, prop_id)?;
+            cast_vote(deps, env, info, prop_id, vec![(option, Decimal::one())])
+        }
+        CCGovExecuteMsg::WeightedVote { prop_id, options } => {
+            cast_vote(deps, env, info, prop_id, options)
+        }
+        CCGovExecuteMsg::RemoteVote {
+            chain,
+            prop_id,
+            options,
+        } => {
+            // the vote is checked and counted by ccgov on the chain of the proposal
+            let vote_msg = app.ibc_client(deps.as_ref()).module_ibc_action(
+                chain.clone(),
+                ccgov_module_info()?,
+                &CCGovIbcMessage::RemoteVote {
+                    prop_id,
+                    voter: info.sender.to_string(),
+                    options,
+                },
+                None,
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("action", "remote_vote")
+                .add_attribute("chain", chain)
+                .add_attribute("prop_id", prop_id.to_string())
+                .add_message(vote_msg))
+        }
+        CCGovExecuteMsg::ExecuteProposal { prop_id } => execute_proposal(deps, &env, &app, prop_id),
+        CCGovExecuteMsg::UpdateConfig { config } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
 
-            // check that the proposal is still open
-            let prop_end_time = prop
-                .start_time
-                .plus_minutes(VOTING_PERIOD_IN_MINUTES.load(deps.storage)?);
+            let config = validated_config(deps.as_ref(), config)?;
+            CONFIG.save(deps.storage, &config)?;
 
-            if prop_end_time <= env.block.time {
-                return Err(ContractError::VotingPeriodHasEnded {});
+            // like at instantiation, the default power contract can be used right away
+            if let Some(default_power_contract) = config.default_power_contract {
+                POWER_CONTRACT_WHITELIST.save(deps.storage, default_power_contract, &Empty {})?;
             }
 
-            // check that the option is valid
-            if !prop.options.contains(&option) {
-                return Err(ContractError::InvalidOption {});
+            Ok(Response::new().add_attribute("action", "update_config"))
+        }
+        CCGovExecuteMsg::AddPowerContract {
+            power_contract_addr,
+        } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+
+            let power_contract_addr = deps.api.addr_validate(&power_contract_addr)?;
+            POWER_CONTRACT_WHITELIST.save(
+                deps.storage,
+                power_contract_addr.to_string(),
+                &Empty {},
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("action", "add_power_contract")
+                .add_attribute("power_contract", power_contract_addr))
+        }
+        CCGovExecuteMsg::RemovePowerContract {
+            power_contract_addr,
+        } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+
+            POWER_CONTRACT_WHITELIST.remove(deps.storage, power_contract_addr.clone());
+
+            Ok(Response::new()
+                .add_attribute("action", "remove_power_contract")
+                .add_attribute("power_contract", power_contract_addr))
+        }
+        CCGovExecuteMsg::AddRemotePowerContract {
+            chain,
+            power_contract_addr,
+        } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+
+            REMOTE_POWER_CONTRACT_WHITELIST.save(
+                deps.storage,
+                (chain.clone(), power_contract_addr.clone()),
+                &Empty {},
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("action", "add_remote_power_contract")
+                .add_attribute("chain", chain)
+                .add_attribute("power_contract", power_contract_addr))
+        }
+        CCGovExecuteMsg::RemoveRemotePowerContract {
+            chain,
+            power_contract_addr,
+        } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+
+            REMOTE_POWER_CONTRACT_WHITELIST
+                .remove(deps.storage, (chain.clone(), power_contract_addr.clone()));
+
+            Ok(Response::new()
+                .add_attribute("action", "remove_remote_power_contract")
+                .add_attribute("chain", chain)
+                .add_attribute("power_contract", power_contract_addr))
+        }
+        CCGovExecuteMsg::AddTrustedModule { chain, module_id } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+
+            TRUSTED_MODULES.save(deps.storage, (chain.clone(), module_id.clone()), &Empty {})?;
+
+            Ok(Response::new()
+                .add_attribute("action", "add_trusted_module")
+                .add_attribute("chain", chain)
+                .add_attribute("module_id", module_id))
+        }
+        CCGovExecuteMsg::RemoveTrustedModule { chain, module_id } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+
+            TRUSTED_MODULES.remove(deps.storage, (chain.clone(), module_id.clone()));
+
+            Ok(Response::new()
+                .add_attribute("action", "remove_trusted_module")
+                .add_attribute("chain", chain)
+                .add_attribute("module_id", module_id))
+        }
+        CCGovExecuteMsg::AddTrustedInstance {
+            chain,
+            contract_addr,
+        } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+
+            TRUSTED_INSTANCES.save(
+                deps.storage,
+                (chain.clone(), contract_addr.clone()),
+                &Empty {},
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("action", "add_trusted_instance")
+                .add_attribute("chain", chain)
+                .add_attribute("contract_addr", contract_addr))
+        }
+        CCGovExecuteMsg::RemoveTrustedInstance {
+            chain,
+            contract_addr,
+        } => {
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+
+            TRUSTED_INSTANCES.remove(deps.storage, (chain.clone(), contract_addr.clone()));
+
+            Ok(Response::new()
+                .add_attribute("action", "remove_trusted_instance")
+                .add_attribute("chain", chain)
+                .add_attribute("contract_addr", contract_addr))
+        }
+        CCGovExecuteMsg::Delegate { to } => {
+            let delegator = info.sender.to_string();
+            let delegate = deps.api.addr_validate(&to)?.to_string();
+            if delegate == delegator {
+                return Err(ContractError::SelfDelegation {});
             }
 
-            // check that the voter has not already voted
-            if VOTE_MAP
-                .load(deps.storage, (prop_id, info.sender.clone().to_string()))
-                .is_ok()
+            // replace an existing delegation
+            if let Some(previous_delegate) =
+                DELEGATIONS.may_load(deps.storage, delegator.clone())?
             {
-                return Err(ContractError::AlreadyVoted {});
+                DELEGATORS.remove(deps.storage, (previous_delegate, delegator.clone()));
             }
-
-            // Get the users voting power by querying the power contract
-            // specified for the proposal
-            let power_msg = to_json_binary(&GetVotingPowerMsg {
-                voter: info.sender.clone().to_string(),
-            })?;
-            let power_response: GetVotingPowerResponse =
-                deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr: prop.power_contract,
-                    msg: power_msg,
-                }))?;
-
-            let vote = Vote {
-                id: vote_id,
-                prop_id,
-                voter: info.sender.clone().to_string(),
-                power: power_response.power,
-                option,
-            };
-            VOTE_MAP.save(
+            let delegator_count = DELEGATORS
+                .prefix(delegate.clone())
+                .keys(deps.storage, None, None, Order::Ascending)
+                .take(MAX_DELEGATORS)
+                .count();
+            if delegator_count >= MAX_DELEGATORS {
+                return Err(ContractError::TooManyDelegators {
+                    max: MAX_DELEGATORS,
+                });
+            }
+            DELEGATIONS.save(deps.storage, delegator.clone(), &delegate)?;
+            DELEGATORS.save(
                 deps.storage,
-                (prop_id, info.sender.clone().to_string()),
-                &vote,
+                (delegate.clone(), delegator.clone()),
+                &Empty {},
             )?;
 
-            // increment the vote id
-            VOTE_ID.save(deps.storage, &(vote_id + 1))?;
+            Ok(Response::new()
+                .add_attribute("action", "delegate")
+                .add_attribute("delegator", delegator)
+                .add_attribute("delegate", delegate))
+        }
+        CCGovExecuteMsg::Undelegate {} => {
+            let delegator = info.sender.to_string();
+            let delegate = DELEGATIONS
+                .may_load(deps.storage, delegator.clone())?
+                .ok_or(ContractError::NotDelegated {})?;
+
+            DELEGATIONS.remove(deps.storage, delegator.clone());
+            DELEGATORS.remove(deps.storage, (delegate.clone(), delegator.clone()));
 
             Ok(Response::new()
-                .add_attribute("action", "vote")
-                .add_attribute("vote_id", vote_id.to_string()))
+                .add_attribute("action", "undelegate")
+                .add_attribute("delegator", delegator)
+                .add_attribute("delegate", delegate))
         }
-        CCGovExecuteMsg::ExecuteProposal { prop_id } => {
-            let prop = PROP_MAP.load(deps.storage, prop_id)?;
-            let prop_end_time = prop
-                .start_time
-                .plus_minutes(VOTING_PERIOD_IN_MINUTES.load(deps.storage)?);
-            if prop_end_time > env.block.time {
-                return Err(ContractError::VotingPeriodNotEnded {});
+        CCGovExecuteMsg::CancelProposal { prop_id } => {
+            let mut prop = PROP_MAP.load(deps.storage, prop_id)?;
+
+            if prop.status == ProposalStatus::Cancelled {
+                return Err(ContractError::ProposalCancelled {});
+            }
+            if EXECUTED_PROPOSALS.has(deps.storage, prop_id) {
+                return Err(ContractError::ProposalAlreadyExecuted {});
             }
 
-            // ensure that we have received resolutions from all prerequisite proposals
-            let mut remote_unresolveds: vec::Vec<u64> = vec![];
-            for prereq_prop_id in prop.prereq_proposals.iter() {
-                if !REMOTE_PROPOSAL_RESOLVED
-                    .load(deps.storage, *prereq_prop_id)
-                    .unwrap_or(false)
-                {
-                    // store that the proposal is unresolved
-                    remote_unresolveds.push(*prereq_prop_id);
+            // the proposer can only withdraw the proposal before anyone voted on it
+            let has_votes = VOTE_MAP
+                .prefix(prop_id)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .next()
+                .is_some()
+                || REMOTE_VOTES
+                    .sub_prefix(prop_id)
+                    .keys(deps.storage, None, None, Order::Ascending)
+                    .next()
+                    .is_some();
+            if info.sender != prop.proposer || has_votes {
+                assert_admin_or_governance(deps.as_ref(), &env, &info)?;
+            }
 
-                    // request the info from the remote chain - we will not be able to resolve this since this goes via IBC,
-                    // but we can request the info already and let the user retry once all remote proposals are resolved
+            prop.status = ProposalStatus::Cancelled;
+            PROP_MAP.save(deps.storage, prop_id, &prop)?;
 
-                    // create ibc client
-                    // load the remote proposal info
-                    let (remote_id, remote_chain, remote_contract_addr) =
-                        REMOTE_PROPOSALS.load(deps.storage, *prereq_prop_id)?;
+            Ok(Response::new()
+                .add_attribute("action", "cancel_proposal")
+                .add_attribute("prop_id", prop_id.to_string())
+                .add_messages(refund_deposit_msgs(deps.as_ref(), &prop)?))
+        }
+        CCGovExecuteMsg::CloseProposal { prop_id } => {
+            // the proposer could otherwise end the vote as soon as it goes their way
+            assert_admin_or_governance(deps.as_ref(), &env, &info)?;
 
-                    let wasm_query = WasmQuery::Smart {
-                        contract_addr: remote_contract_addr.clone(),
-                        msg: to_json_binary(&QueryMsg::Module(CCGovQueryMsg::QueryTally {
-                            prop_id: remote_id,
-                        }))?,
-                    };
+            let mut prop = PROP_MAP.load(deps.storage, prop_id)?;
+            if prop.expiration != (Expiration::Never {}) {
+                return Err(ContractError::CannotCloseProposal {});
+            }
+            match prop.status {
+                ProposalStatus::Open => {}
+                ProposalStatus::DepositPeriod => return Err(ContractError::VotingNotStarted {}),
+                ProposalStatus::Cancelled => return Err(ContractError::ProposalCancelled {}),
+                _ => return Err(ContractError::VotingPeriodHasEnded {}),
+            }
 
-                    let remote_prop_msg = RemoteProposalMsg {
-                        prop_id: *prereq_prop_id,
-                        remote_chain_id: remote_chain.clone(),
-                        remote_contract_addr: remote_contract_addr.clone(),
-                    };
+            // voting ends at the current block
+            prop.expiration = Expiration::AtTime(env.block.time);
+            PROP_MAP.save(deps.storage, prop_id, &prop)?;
 
-                    let callback_info = CallbackInfo::new(
-                        QUERY_TALLY_CALLBACK_ID,
-                        Some(to_json_binary(&remote_prop_msg)?),
-                    );
-                    let msg = app.ibc_client(deps.as_ref()).ibc_query(
-                        remote_chain,
-                        wasm_query,
-                        callback_info,
-                    )?;
-
-                    return Ok(Response::new()
-                        .add_attribute("action", "execute_proposal")
-                        .add_attribute("result", "remote_proposal_unresolved")
-                        .add_event(Event::new("remote_proposal_unresolved"))
-                        .add_message(msg));
-                }
-            }
+            Ok(Response::new()
+                .add_attribute("action", "close_proposal")
+                .add_attribute("prop_id", prop_id.to_string()))
+        }
+    }
+}
+
+// Executes a proposal: tallies the votes once the results of all prerequisite remote proposals are
+// known, and dispatches the messages of the winning option.
+fn execute_proposal(deps: DepsMut, env: &Env, app: &CCGovApp, prop_id: u64) -> CCGovResult {
+    let mut prop = PROP_MAP.load(deps.storage, prop_id)?;
+
+    if prop.status == ProposalStatus::Cancelled {
+        return Err(ContractError::ProposalCancelled {});
+    }
+
+    // proposals can only be executed once
+    if EXECUTED_PROPOSALS.has(deps.storage, prop_id) {
+        return Err(ContractError::ProposalAlreadyExecuted {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // open proposals can only be executed early if their outcome is already decided
+    let closed_early = current_status(env, &prop) == ProposalStatus::Open;
+    if closed_early && !outcome_decided(deps.as_ref(), env, &config, &prop)? {
+        return Err(ContractError::VotingPeriodNotEnded {});
+    }
+
+    // proposals that did not reach the minimum deposit fail and their deposits are refunded
+    if prop.status == ProposalStatus::DepositPeriod {
+        if env.block.time < prop.deposit_end_time {
+            return Err(ContractError::DepositPeriodNotEnded {});
+        }
+        let outcome = DEPOSIT_NOT_REACHED_OUTCOME.to_string();
+        EXECUTED_PROPOSALS.save(deps.storage, prop_id, &outcome)?;
+        prop.status = ProposalStatus::Failed;
+        PROP_MAP.save(deps.storage, prop_id, &prop)?;
+
+        return Ok(Response::new()
+            .add_attribute("action", "execute_proposal")
+            .add_attribute("result", outcome.clone())
+            .add_messages(deposit_msgs(deps.as_ref(), &config, &prop, &outcome)?)
+            .add_messages(subscriber_msgs(deps.as_ref(), env, app, prop_id)?));
+    }
+
+    // ensure that we have received resolutions from all prerequisite proposals
+    let mut remote_unresolveds: vec::Vec<u64> = vec![];
+    let mut query_msgs = vec![];
+    for prereq_prop_id in prop.prereq_proposals.iter() {
+        if !REMOTE_PROPOSAL_RESOLVED
+            .load(deps.storage, *prereq_prop_id)
+            .unwrap_or(false)
+        {
+            // store that the proposal is unresolved
+            remote_unresolveds.push(*prereq_prop_id);
+
+            // request the info from the remote chain - we will not be able to resolve this since this goes via IBC,
+            // but we can request the info already and let the user retry once all remote proposals are resolved
+
+            // create ibc client
+            // load the remote proposal info
+            let PrerequisiteProposal {
+                prop_id: remote_id,
+                chain: remote_chain,
+                contract_addr: remote_contract_addr,
+                ..
+            } = REMOTE_PROPOSALS.load(deps.storage, *prereq_prop_id)?;
+
+            let wasm_query = WasmQuery::Smart {
+                contract_addr: remote_contract_addr.clone(),
+                msg: to_json_binary(&QueryMsg::Module(CCGovQueryMsg::QueryTally {
+                    prop_id: remote_id,
+                }))?,
+            };
+
+            let remote_prop_msg = RemoteProposalMsg {
+                parent_prop_id: prop_id,
+                prop_id: *prereq_prop_id,
+                remote_chain_id: remote_chain.clone(),
+                remote_contract_addr: remote_contract_addr.clone(),
+            };
+
+            let callback_info = CallbackInfo::new(
+                QUERY_TALLY_CALLBACK_ID,
+                Some(to_json_binary(&remote_prop_msg)?),
+            );
+            query_msgs.push(app.ibc_client(deps.as_ref()).ibc_query(
+                remote_chain,
+                wasm_query,
+                callback_info,
+            )?);
+        }
+    }
+
+    // the quorum needs the total power of every remote voting chain, which is requested again if
+    // an earlier query failed
+    let total_power_msgs = remote_total_power_msgs(deps.as_ref(), app, &prop)?;
+
+    // the results of all unresolved prerequisites are requested at once
+    if !remote_unresolveds.is_empty() || !total_power_msgs.is_empty() {
+        prop.status = ProposalStatus::PendingRemote;
+        PROP_MAP.save(deps.storage, prop_id, &prop)?;
+
+        let mut response = Response::new().add_attribute("action", "execute_proposal");
+        if remote_unresolveds.is_empty() {
+            response = response.add_attribute("result", "remote_total_power_unknown");
+        } else {
+            let prereq_prop_ids = remote_unresolveds
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            response = response
+                .add_attribute("result", "remote_proposal_unresolved")
+                .add_event(
+                    Event::new("remote_proposal_unresolved")
+                        .add_attribute("prereq_prop_ids", prereq_prop_ids),
+                );
+        }
+        return Ok(response
+            .add_messages(query_msgs)
+            .add_messages(total_power_msgs));
+    }
+
+    // get the tally for the proposal
+    let tally = query_tally(deps.as_ref(), prop_id)?;
+
+    // determine the outcome according to the quorum and threshold
+    let outcome = proposal_outcome(&config, &prop, &tally)?;
+
+    // store it in the executed proposals
+    EXECUTED_PROPOSALS.save(deps.storage, prop_id, &outcome)?;
+
+    // dispatch the messages attached to the winning option, if there is one
+    let msgs = prop
+        .option(&outcome)
+        .map(|option| option.msgs.clone())
+        .unwrap_or_default();
+
+    prop.status = match outcome.as_str() {
+        FAILED_QUORUM_OUTCOME => ProposalStatus::Failed,
+        THRESHOLD_NOT_REACHED_OUTCOME | VETOED_OUTCOME => ProposalStatus::Rejected,
+        _ if msgs.is_empty() => ProposalStatus::Passed,
+        _ => ProposalStatus::Executed,
+    };
+    PROP_MAP.save(deps.storage, prop_id, &prop)?;
+
+    let response = Response::new()
+        .add_attribute("action", "execute_proposal")
+        .add_attribute("result", outcome.clone())
+        .add_attribute("closed_early", closed_early.to_string())
+        .add_messages(msgs)
+        .add_messages(deposit_msgs(deps.as_ref(), &config, &prop, &outcome)?)
+        .add_messages(subscriber_msgs(deps.as_ref(), env, app, prop_id)?);
+
+    Ok(response)
+}
 
-            // get the tally for the proposal
-            let option_votes = query_tally(deps.as_ref(), prop_id)?;
+// Returns the IBC queries for the total power of each remote voting chain of a proposal whose total
+// power is not known yet, as of its start time. The start time needs to have passed, so that the
+// power contracts can answer for it. A proposal is only executed once all of these are known.
+fn remote_total_power_msgs(
+    deps: Deps,
+    app: &CCGovApp,
+    prop: &Proposal,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let ibc_client = app.ibc_client(deps);
+    prop.remote_voting_chains
+        .iter()
+        .filter(|voting_chain| {
+            !REMOTE_VOTING_TOTAL_POWER.has(deps.storage, (prop.id, voting_chain.chain.clone()))
+        })
+        .map(|voting_chain| {
+            let total_power_query = WasmQuery::Smart {
+                contract_addr: voting_chain.power_contract.clone(),
+                msg: to_json_binary(&PowerContractQueryMsg::GetTotalPowerMsg(GetTotalPowerMsg {
+                    time: Some(prop.start_time),
+                }))?,
+            };
+            let total_power_msg = RemoteTotalPowerMsg {
+                prop_id: prop.id,
+                chain: voting_chain.chain.clone(),
+                time: prop.start_time,
+            };
+            Ok(ibc_client.ibc_query(
+                voting_chain.chain.clone(),
+                total_power_query,
+                CallbackInfo::new(
+                    REMOTE_TOTAL_POWER_CALLBACK_ID,
+                    Some(to_json_binary(&total_power_msg)?),
+                ),
+            )?)
+        })
+        .collect()
+}
+
+// The module info of ccgov on other chains, the target of module IBC messages.
+fn ccgov_module_info() -> Result<ModuleInfo, ContractError> {
+    Ok(ModuleInfo::from_id(CCGOV_ID, APP_VERSION.into())?)
+}
+
+// Returns the messages that send the tally of an executed proposal to every chain that
+// subscribed to it.
+fn subscriber_msgs(
+    deps: Deps,
+    env: &Env,
+    app: &CCGovApp,
+    prop_id: u64,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let subscribers = SUBSCRIBERS
+        .sub_prefix(prop_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    if subscribers.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let tally = query_tally(deps, prop_id)?;
+    subscribers
+        .into_iter()
+        .map(|((chain, remote_prop_id), parent_prop_id)| {
+            proposal_result_msg(
+                deps,
+                app,
+                chain,
+                CCGovIbcMessage::ProposalResult {
+                    prop_id,
+                    parent_prop_id,
+                    remote_prop_id,
+                    tally: tally.clone(),
+                    contract_addr: env.contract.address.to_string(),
+                },
+            )
+        })
+        .collect()
+}
 
-            // find which option has the most votes
-            // make a map from options to their index in the proposal options
-            let mut option_index_map = std::collections::HashMap::new();
-            for (i, option) in prop.options.iter().enumerate() {
-                option_index_map.insert(option.clone(), i);
+// Sends a ProposalResult to the ccgov instance on the given chain.
+fn proposal_result_msg(
+    deps: Deps,
+    app: &CCGovApp,
+    chain: String,
+    msg: CCGovIbcMessage,
+) -> Result<CosmosMsg, ContractError> {
+    Ok(app
+        .ibc_client(deps)
+        .module_ibc_action(chain, ccgov_module_info()?, &msg, None)?)
+}
+
+// Checks that normalizations do not scale remote power to nothing or by more than MAX_MULTIPLIER,
+// and that the weights of a WeightedSum are positive and given at most once for the chain of each
+// prerequisite.
+fn assert_valid_aggregation(
+    prereq_proposals: &[PrerequisiteProposal],
+    remote_voting_chains: &[RemoteVotingChain],
+    aggregation_strategy: Option<&AggregationStrategy>,
+) -> Result<(), ContractError> {
+    let normalizations = prereq_proposals
+        .iter()
+        .map(|prereq| &prereq.normalization)
+        .chain(
+            remote_voting_chains
+                .iter()
+                .map(|voting_chain| &voting_chain.normalization),
+        );
+    for normalization in normalizations {
+        let valid = match normalization {
+            Normalization::Multiplier { factor } => {
+                !factor.is_zero() && *factor <= Decimal::from_ratio(MAX_MULTIPLIER, 1u64)
             }
+            Normalization::Cap { max_power } => *max_power > 0,
+            Normalization::None | Normalization::FractionOfTotalPower => true,
+        };
+        if !valid {
+            return Err(ContractError::InvalidNormalization {
+                max: MAX_MULTIPLIER,
+            });
+        }
+    }
 
-            let mut max_votes = 0;
-            let mut max_index = 0;
-            for (option, votes) in option_votes.iter() {
-                if *votes > max_votes {
-                    max_votes = *votes;
-                    max_index = option_index_map.get(option).unwrap().clone();
-                }
+    if let Some(AggregationStrategy::WeightedSum { weights }) = aggregation_strategy {
+        for (index, (chain, weight)) in weights.iter().enumerate() {
+            if weight.is_zero()
+                || !prereq_proposals.iter().any(|prereq| &prereq.chain == chain)
+                || weights[..index].iter().any(|(other, _)| other == chain)
+            {
+                return Err(ContractError::InvalidAggregationWeights {});
             }
+        }
+    }
+    Ok(())
+}
 
-            // load the old list of executed proposals
-            let mut executed_proposals = EXECUTED_PROPOSALS.load(deps.storage)?;
-            // store it in the executed proposals
-            executed_proposals.push((prop_id, prop.options[max_index].clone()));
-            EXECUTED_PROPOSALS.save(deps.storage, &executed_proposals)?;
+// Checks that an explicit expiration of a proposal is not already expired. Expirations in time
+// need to give a voting period within the bounds from the config.
+fn assert_valid_expiration(
+    config: &Config,
+    env: &Env,
+    start_time: Timestamp,
+    expiration: &Expiration,
+) -> Result<(), ContractError> {
+    let valid = match expiration {
+        Expiration::AtTime(end_time) => {
+            *end_time >= start_time.plus_minutes(config.min_voting_period_in_minutes)
+                && *end_time <= start_time.plus_minutes(config.max_voting_period_in_minutes)
+        }
+        Expiration::AtHeight(_) => !expiration.is_expired(&env.block),
+        Expiration::Never {} => true,
+    };
+    if !valid {
+        return Err(ContractError::InvalidVotingPeriod {});
+    }
+    Ok(())
+}
 
-            let result = prop.options[max_index].clone();
-            let response = Response::new()
-                .add_attribute("action", "execute_proposal")
-                .add_attribute("result", result.clone());
+// Adds the funds sent with the message to the deposit of the proposal.
+fn add_deposit(
+    storage: &mut dyn Storage,
+    config: &Config,
+    prop: &mut Proposal,
+    info: &MessageInfo,
+) -> Result<(), ContractError> {
+    // only denoms of the minimum deposit are accepted, so no funds get stuck
+    if info
+        .funds
+        .iter()
+        .any(|coin| !config.min_deposit.iter().any(|min| min.denom == coin.denom))
+    {
+        return Err(ContractError::InvalidDeposit {});
+    }
+    if info.funds.is_empty() {
+        return Ok(());
+    }
+
+    let key = (prop.id, info.sender.to_string());
+    let mut deposit = Coins::try_from(DEPOSITS.may_load(storage, key.clone())?.unwrap_or_default())
+        .map_err(|_| ContractError::InvalidDeposit {})?;
+    let mut total_deposit =
+        Coins::try_from(prop.deposit.clone()).map_err(|_| ContractError::InvalidDeposit {})?;
+    for coin in info.funds.iter() {
+        deposit.add(coin.clone())?;
+        total_deposit.add(coin.clone())?;
+    }
+    DEPOSITS.save(storage, key, &deposit.into_vec())?;
+    prop.deposit = total_deposit.into_vec();
+    Ok(())
+}
+
+// Checks whether the deposit of the proposal reached the minimum deposit from the config.
+fn deposit_reached(config: &Config, prop: &Proposal) -> bool {
+    config.min_deposit.iter().all(|min| {
+        prop.deposit
+            .iter()
+            .any(|coin| coin.denom == min.denom && coin.amount >= min.amount)
+    })
+}
+
+// Returns the messages that pay out the deposits of an executed proposal. Deposits are refunded,
+// unless the proposal was vetoed or failed the quorum; then they are sent to the deposit
+// recipient or burned.
+fn deposit_msgs(
+    deps: Deps,
+    config: &Config,
+    prop: &Proposal,
+    outcome: &str,
+) -> StdResult<Vec<BankMsg>> {
+    if prop.deposit.is_empty() {
+        return Ok(vec![]);
+    }
+
+    if outcome == VETOED_OUTCOME || outcome == FAILED_QUORUM_OUTCOME {
+        let msg = match &config.deposit_recipient {
+            Some(recipient) => BankMsg::Send {
+                to_address: recipient.clone(),
+                amount: prop.deposit.clone(),
+            },
+            None => BankMsg::Burn {
+                amount: prop.deposit.clone(),
+            },
+        };
+        return Ok(vec![msg]);
+    }
+
+    refund_deposit_msgs(deps, prop)
+}
 
-            Ok(response)
+// Returns the messages that refund the deposits of a proposal to each depositor.
+fn refund_deposit_msgs(deps: Deps, prop: &Proposal) -> StdResult<Vec<BankMsg>> {
+    DEPOSITS
+        .prefix(prop.id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(depositor, amount)| BankMsg::Send {
+                to_address: depositor,
+                amount,
+            })
+        })
+        .collect()
+}
+
+// Records the vote of the sender on a proposal, splitting their power across the given
+// options according to the weights.
+fn cast_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    prop_id: u64,
+    options: Vec<(String, Decimal)>,
+) -> CCGovResult {
+    let mut vote_id = VOTE_ID.load(deps.storage)?;
+    let prop = PROP_MAP.load(deps.storage, prop_id)?;
+
+    assert_voting_open(&env, &prop)?;
+    assert_valid_vote_options(&prop, &options)?;
+
+    // check that the voter has not already voted, unless votes can be changed
+    let previous_vote =
+        VOTE_MAP.may_load(deps.storage, (prop_id, info.sender.clone().to_string()))?;
+    if previous_vote.is_some() && !CONFIG.load(deps.storage)?.allow_revoting {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    // a changed vote replaces the previous one and keeps its id
+    if let Some(previous_vote) = &previous_vote {
+        vote_id = previous_vote.id;
+    }
+
+    let voter = info.sender.to_string();
+    let own_power = query_voting_power(deps.as_ref(), &prop, voter.clone())?;
+    let mut events = vec![];
+
+    // a changed vote already includes the voter's own power and earlier delegated power
+    let mut power = match &previous_vote {
+        Some(previous_vote) => previous_vote.power,
+        None => own_power,
+    };
+
+    // add the power of delegators that has not been counted on this proposal yet
+    for (delegator, delegator_power) in uncounted_delegators(deps.as_ref(), &prop, &voter)? {
+        DELEGATED_VOTES.save(deps.storage, (prop_id, delegator), &voter)?;
+        power = add_power(power, delegator_power)?;
+    }
+
+    // voting directly overrides the vote of the delegate that voted with the voter's power
+    if previous_vote.is_none() {
+        if let Some(delegate) = DELEGATED_VOTES.may_load(deps.storage, (prop_id, voter.clone()))? {
+            let mut delegate_vote = VOTE_MAP.load(deps.storage, (prop_id, delegate.clone()))?;
+            delegate_vote.power = delegate_vote
+                .power
+                .checked_sub(own_power)
+                .ok_or(ContractError::PowerOverflow {})?;
+            VOTE_MAP.save(deps.storage, (prop_id, delegate.clone()), &delegate_vote)?;
+            DELEGATED_VOTES.remove(deps.storage, (prop_id, voter.clone()));
+
+            events.push(
+                Event::new("delegate_vote_overridden")
+                    .add_attribute("prop_id", prop_id.to_string())
+                    .add_attribute("delegator", voter.clone())
+                    .add_attribute("delegate", delegate),
+            );
+        }
+    }
+
+    let vote = Vote {
+        id: vote_id,
+        prop_id,
+        voter,
+        power,
+        options,
+    };
+    VOTE_MAP.save(
+        deps.storage,
+        (prop_id, info.sender.clone().to_string()),
+        &vote,
+    )?;
+    VOTES_BY_VOTER.save(
+        deps.storage,
+        (info.sender.clone().to_string(), prop_id),
+        &Empty {},
+    )?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "vote")
+        .add_attribute("vote_id", vote_id.to_string())
+        .add_events(events);
+
+    match previous_vote {
+        Some(previous_vote) => {
+            response = response.add_event(
+                Event::new("vote_changed")
+                    .add_attribute("prop_id", prop_id.to_string())
+                    .add_attribute("voter", vote.voter)
+                    .add_attribute(
+                        "previous_options",
+                        format_vote_options(&previous_vote.options),
+                    )
+                    .add_attribute("options", format_vote_options(&vote.options)),
+            );
+        }
+        None => {
+            // increment the vote id
+            VOTE_ID.save(deps.storage, &(vote_id + 1))?;
+        }
+    }
+
+    Ok(response)
+}
+
+// Checks that the proposal is open for voting.
+fn assert_voting_open(env: &Env, prop: &Proposal) -> Result<(), ContractError> {
+    if prop.status == ProposalStatus::Cancelled {
+        return Err(ContractError::ProposalCancelled {});
+    }
+    if prop.status == ProposalStatus::DepositPeriod || env.block.time < prop.start_time {
+        return Err(ContractError::VotingNotStarted {});
+    }
+    if current_status(env, prop) != ProposalStatus::Open {
+        return Err(ContractError::VotingPeriodHasEnded {});
+    }
+    Ok(())
+}
+
+// Checks that the options of a vote are options of the proposal, appear only once, and have
+// positive weights that sum to one.
+fn assert_valid_vote_options(
+    prop: &Proposal,
+    options: &[(String, Decimal)],
+) -> Result<(), ContractError> {
+    for (index, (option, _)) in options.iter().enumerate() {
+        if prop.option(option).is_none() || options[..index].iter().any(|(o, _)| o == option) {
+            return Err(ContractError::InvalidOption {});
         }
     }
+
+    // weights that overflow when summed are invalid as well
+    let sum = options
+        .iter()
+        .try_fold(Decimal::zero(), |sum, (_, weight)| sum.checked_add(*weight))
+        .map_err(|_| ContractError::InvalidVoteWeights {})?;
+    if options.iter().any(|(_, weight)| weight.is_zero()) || sum != Decimal::one() {
+        return Err(ContractError::InvalidVoteWeights {});
+    }
+    Ok(())
+}
+
+// Formats weighted vote options as e.g. "approve:0.7,reject:0.3" for event attributes.
+fn format_vote_options(options: &[(String, Decimal)]) -> String {
+    options
+        .iter()
+        .map(|(option, weight)| format!("{option}:{weight}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Checks that the sender is either the admin from the config, or the contract itself,
+// which is the case for messages dispatched by executed proposals.
+pub fn assert_admin_or_governance(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<(), ContractError> {
+    if info.sender == env.contract.address {
+        return Ok(());
+    }
+    match CONFIG.load(deps.storage)?.admin {
+        Some(admin) if info.sender == admin => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
 }
 
-pub fn get_total_voted_power(deps: Deps, prop_id: u64) -> StdResult<u64> {
+pub fn get_total_voted_power(deps: Deps, prop_id: u64) -> Result<u64, ContractError> {
     let votes = VOTE_MAP
         .prefix(prop_id)
         .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
         .collect::<StdResult<Vec<_>>>()?;
     let mut total_power = 0;
     for vote in votes {
-        total_power += vote.1.power;
+        total_power = add_power(total_power, vote.1.power)?;
     }
     Ok(total_power)
 }
@@ -311,8 +1268,8 @@ pub fn get_total_voted_power(deps: Deps, prop_id: u64) -> StdResult<u64> {
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query_handler(
     deps: Deps,
-    _env: Env,
-    app: &CCGovApp,
+    env: Env,
+    _app: &CCGovApp,
     msg: CCGovQueryMsg,
 ) -> CCGovResult<Binary> {
     match msg {
@@ -323,15 +1280,39 @@ pub fn query_handler(
             })?)
         }
         CCGovQueryMsg::QueryProposal { prop_id } => {
+            let mut prop = PROP_MAP.load(deps.storage, prop_id)?;
+            prop.status = current_status(&env, &prop);
+            Ok(to_json_binary(&QueryProposalResponse { prop })?)
+        }
+        CCGovQueryMsg::QueryPrerequisites { prop_id } => {
             let prop = PROP_MAP.load(deps.storage, prop_id)?;
-            Ok(to_json_binary(&QueryProposalResponse { prop: prop })?)
+            let prerequisites = prop
+                .prereq_proposals
+                .iter()
+                .map(|remote_prop_id| {
+                    Ok(PrerequisiteStatus {
+                        remote_prop_id: *remote_prop_id,
+                        prereq: REMOTE_PROPOSALS.load(deps.storage, *remote_prop_id)?,
+                        validation: REMOTE_PROPOSAL_VALIDATIONS
+                            .may_load(deps.storage, *remote_prop_id)?,
+                        resolved: REMOTE_PROPOSAL_RESOLVED
+                            .may_load(deps.storage, *remote_prop_id)?
+                            .unwrap_or(false),
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&QueryPrerequisitesResponse {
+                prerequisites,
+            })?)
         }
         CCGovQueryMsg::QueryVote { prop_id, voter } => {
             let vote = VOTE_MAP.load(deps.storage, (prop_id, voter))?;
-            Ok(to_json_binary(&QueryVoteResponse { vote: vote })?)
+            Ok(to_json_binary(&QueryVoteResponse { vote })?)
         }
         CCGovQueryMsg::QueryExecutedProposals {} => {
-            let executed_proposals = EXECUTED_PROPOSALS.load(deps.storage)?;
+            let executed_proposals = EXECUTED_PROPOSALS
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
             Ok(to_json_binary(&QueryExecutedProposalsResponse {
                 executed_proposals,
             })?)
@@ -339,7 +1320,162 @@ pub fn query_handler(
         CCGovQueryMsg::QueryTally { prop_id } => {
             let tally = query_tally(deps, prop_id)?;
 
-            Ok(to_json_binary(&QueryTallyResponse { tally })?)
+            Ok(to_json_binary(&tally)?)
+        }
+        CCGovQueryMsg::Config {} => {
+            let config = CONFIG.load(deps.storage)?;
+            Ok(to_json_binary(&ConfigResponse { config })?)
+        }
+        CCGovQueryMsg::ListPowerContracts { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let power_contracts = POWER_CONTRACT_WHITELIST
+                .keys(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&ListPowerContractsResponse {
+                power_contracts,
+            })?)
+        }
+        CCGovQueryMsg::ListRemotePowerContracts { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let remote_power_contracts = REMOTE_POWER_CONTRACT_WHITELIST
+                .keys(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&ListRemotePowerContractsResponse {
+                remote_power_contracts,
+            })?)
+        }
+        CCGovQueryMsg::ListTrustedModules { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let trusted_modules = TRUSTED_MODULES
+                .keys(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&ListTrustedModulesResponse {
+                trusted_modules,
+            })?)
+        }
+        CCGovQueryMsg::ListTrustedInstances { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let trusted_instances = TRUSTED_INSTANCES
+                .keys(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&ListTrustedInstancesResponse {
+                trusted_instances,
+            })?)
+        }
+        CCGovQueryMsg::Delegation { delegator } => {
+            let delegate = DELEGATIONS.may_load(deps.storage, delegator)?;
+            Ok(to_json_binary(&DelegationResponse { delegate })?)
+        }
+        CCGovQueryMsg::ListDelegators {
+            delegate,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let delegators = DELEGATORS
+                .prefix(delegate)
+                .keys(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&ListDelegatorsResponse { delegators })?)
+        }
+        CCGovQueryMsg::DelegatedPower { delegate, prop_id } => {
+            let prop = PROP_MAP.load(deps.storage, prop_id)?;
+            let power = uncounted_delegators(deps, &prop, &delegate)?
+                .iter()
+                .try_fold(0, |sum, (_, power)| add_power(sum, *power))?;
+            Ok(to_json_binary(&DelegatedPowerResponse { power })?)
+        }
+        CCGovQueryMsg::ListProposals {
+            start_after,
+            limit,
+            status,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let mut proposals = vec![];
+            for item in PROP_MAP.range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            ) {
+                let (_, mut prop) = item?;
+                prop.status = current_status(&env, &prop);
+                if status.as_ref().is_none_or(|status| *status == prop.status) {
+                    proposals.push(prop);
+                }
+                if proposals.len() >= limit {
+                    break;
+                }
+            }
+            Ok(to_json_binary(&ListProposalsResponse { proposals })?)
+        }
+        CCGovQueryMsg::ListVotes {
+            prop_id,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let votes = VOTE_MAP
+                .prefix(prop_id)
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, vote)| vote))
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&ListVotesResponse { votes })?)
+        }
+        CCGovQueryMsg::ListVotesByVoter {
+            voter,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let votes = VOTES_BY_VOTER
+                .prefix(voter.clone())
+                .keys(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|prop_id| VOTE_MAP.load(deps.storage, (prop_id?, voter.clone())))
+                .collect::<StdResult<Vec<_>>>()?;
+            Ok(to_json_binary(&ListVotesResponse { votes })?)
         }
     }
 }
@@ -353,132 +1489,873 @@ pub fn migrate_handler(
     Ok(app.response("migrate"))
 }
 
-pub fn query_tally(deps: Deps, prop_id: u64) -> Result<Vec<(String, u64)>, ContractError> {
-    let prop = PROP_MAP.load(deps.storage, prop_id)?;
+// Returns the status of the proposal at the current block. Open proposals are only
+// moved on when they are executed, so their status is derived from the voting period.
+pub fn current_status(env: &Env, prop: &Proposal) -> ProposalStatus {
+    if prop.status == ProposalStatus::Open && prop.expiration.is_expired(&env.block) {
+        return ProposalStatus::PendingExecution;
+    }
+    prop.status.clone()
+}
 
-    let mut option_votes = vec![0; prop.options.len()];
+// Determines the outcome of a proposal from its tally. The option with the most votes wins
+// if more than the threshold of the votes cast went to it. If less than the quorum of the
+// total power voted, the proposal fails regardless of the votes.
+pub fn tally_outcome(
+    config: &Config,
+    prop: &Proposal,
+    tally: &[(String, u64)],
+    total_power: u64,
+) -> Result<String, ContractError> {
+    let votes_of_kind = |kind: OptionKind| {
+        tally
+            .iter()
+            .filter(|(option, _)| prop.option(option).map(|option| &option.kind) == Some(&kind))
+            .try_fold(0, |sum, (_, votes)| add_power(sum, *votes))
+    };
+    let votes_cast = tally
+        .iter()
+        .try_fold(0, |sum, (_, votes)| add_power(sum, *votes))?;
+
+    let turnout = if total_power == 0 {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(votes_cast, total_power)
+    };
+    if turnout < config.quorum {
+        return Ok(FAILED_QUORUM_OUTCOME.to_string());
+    }
 
-    // check that all prerequisite proposals have been executed
-    for prereq_prop_id in prop.prereq_proposals.iter() {
-        for option in prop.options.iter() {
-            // load the tally for the option
-            let tally = REMOTE_PROPOSALS_TALLIES
-                .load(deps.storage, (*prereq_prop_id, option.clone()))
-                .unwrap_or(0);
-
-            // add the tally to the total
-            let option_index = prop
-                .options
-                .iter()
-                .position(|x| *x == option.clone())
-                .unwrap();
+    // too many NoWithVeto votes reject the proposal outright
+    let veto_votes = votes_of_kind(OptionKind::NoWithVeto)?;
+    if veto_votes > 0 && Decimal::from_ratio(veto_votes, votes_cast) > config.veto_threshold {
+        return Ok(VETOED_OUTCOME.to_string());
+    }
+
+    // abstaining votes do not count toward the threshold
+    let votes_cast = votes_cast - votes_of_kind(OptionKind::Abstain)?;
+
+    // find which standard option has the most votes, a tie has no winner
+    let mut max_votes = 0;
+    let mut winning_option = None;
+    for (option, votes) in tally.iter() {
+        if prop.option(option).map(|option| &option.kind) != Some(&OptionKind::Standard) {
+            continue;
+        }
+        if *votes > max_votes {
+            max_votes = *votes;
+            winning_option = Some(option);
+        } else if *votes == max_votes {
+            winning_option = None;
+        }
+    }
 
-            option_votes[option_index] += tally;
+    Ok(match winning_option {
+        Some(option) if Decimal::from_ratio(max_votes, votes_cast) > config.threshold => {
+            option.clone()
         }
+        _ => THRESHOLD_NOT_REACHED_OUTCOME.to_string(),
+    })
+}
+
+// Checks whether the outcome of an open proposal can no longer change, no matter how the local
+// power that has not voted yet is used. This is the case if the outcome stays the same when all
+// of the remaining power goes to any single option, which are the extremes for the quorum, the
+// veto and the threshold. The tallies of all prerequisite remote proposals and the total power
+// of all remote voting chains need to be known, and votes must not be changeable.
+pub fn outcome_decided(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    prop: &Proposal,
+) -> Result<bool, ContractError> {
+    if config.allow_revoting || env.block.time < prop.start_time {
+        return Ok(false);
+    }
+    // without the total power of a remote voting chain, the quorum is not known yet
+    if !remote_results_known(deps.storage, prop) {
+        return Ok(false);
     }
 
-    let votes = VOTE_MAP
-        .prefix(prop_id)
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+    let tally = query_tally(deps, prop.id)?;
+    let local = &tally.sources[0];
+    let votes_cast = local
+        .tally
+        .iter()
+        .try_fold(0, |sum, (_, votes)| add_power(sum, *votes))?;
+    let remaining_power = local.total_power.saturating_sub(votes_cast);
+
+    // local votes count fully toward the combined tally under every aggregation strategy
+    let outcome = proposal_outcome(config, prop, &tally)?;
+    for index in 0..tally.tally.len() {
+        let mut extreme_tally = tally.clone();
+        extreme_tally.tally[index].1 = add_power(tally.tally[index].1, remaining_power)?;
+        extreme_tally.sources[0].tally[index].1 = add_power(local.tally[index].1, remaining_power)?;
+        if proposal_outcome(config, prop, &extreme_tally)? != outcome {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+// Queries the power of a voter from the power contract of the proposal,
+// as of the start of the proposal.
+pub fn query_voting_power(deps: Deps, prop: &Proposal, voter: String) -> StdResult<u64> {
+    let power_response: GetVotingPowerResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: prop.power_contract.clone(),
+            msg: to_json_binary(&PowerContractQueryMsg::GetVotingPowerMsg(
+                GetVotingPowerMsg {
+                    voter,
+                    time: Some(prop.start_time),
+                },
+            ))?,
+        }))?;
+    Ok(power_response.power)
+}
+
+// Returns the delegators of the delegate, with their power, whose power has not been
+// counted on the proposal yet, i.e. that have not voted directly or through another delegate.
+pub fn uncounted_delegators(
+    deps: Deps,
+    prop: &Proposal,
+    delegate: &str,
+) -> StdResult<Vec<(String, u64)>> {
+    let delegators = DELEGATORS
+        .prefix(delegate.to_string())
+        .keys(deps.storage, None, None, Order::Ascending)
         .collect::<StdResult<Vec<_>>>()?;
 
-    for vote in votes {
-        let option_index = prop
+    let mut uncounted = vec![];
+    for delegator in delegators {
+        if VOTE_MAP.has(deps.storage, (prop.id, delegator.clone()))
+            || DELEGATED_VOTES.has(deps.storage, (prop.id, delegator.clone()))
+        {
+            continue;
+        }
+        let power = query_voting_power(deps, prop, delegator.clone())?;
+        uncounted.push((delegator, power));
+    }
+    Ok(uncounted)
+}
+
+// Queries the total power of a power contract.
+pub fn query_total_power(deps: Deps, power_contract: String, time: Timestamp) -> StdResult<u64> {
+    let total_power_response: GetTotalPowerResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: power_contract,
+            msg: to_json_binary(&PowerContractQueryMsg::GetTotalPowerMsg(GetTotalPowerMsg {
+                time: Some(time),
+            }))?,
+        }))?;
+    Ok(total_power_response.power)
+}
+
+pub fn query_tally(deps: Deps, prop_id: u64) -> Result<QueryTallyResponse, ContractError> {
+    let prop = PROP_MAP.load(deps.storage, prop_id)?;
+
+    let local_total_power = query_total_power(deps, prop.power_contract.clone(), prop.start_time)?;
+    let mut sources = vec![local_tally(deps, &prop, local_total_power)?];
+    for prereq_prop_id in prop.prereq_proposals.iter() {
+        let prereq = REMOTE_PROPOSALS.load(deps.storage, *prereq_prop_id)?;
+        let remote_total_power = REMOTE_PROPOSALS_TOTAL_POWER
+            .may_load(deps.storage, *prereq_prop_id)?
+            .unwrap_or(0);
+        // remote power is normalized before it is combined with the local power
+        let normalize = |power: u64| {
+            prereq
+                .normalization
+                .apply(power, remote_total_power, local_total_power)
+        };
+
+        let tally = prop
             .options
             .iter()
-            .position(|x| *x == vote.1.option)
-            .unwrap();
-        option_votes[option_index] += vote.1.power;
+            .map(|option| {
+                // the tally is zero as long as the result of the remote proposal is not known
+                let votes = REMOTE_PROPOSALS_TALLIES
+                    .may_load(deps.storage, (*prereq_prop_id, option.name.clone()))?
+                    .unwrap_or(0);
+                Ok((option.name.clone(), normalize(votes)?))
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+        sources.push(SourceTally {
+            chain: Some(prereq.chain.clone()),
+            prop_id: prereq.prop_id,
+            tally,
+            total_power: normalize(remote_total_power)?,
+            outcome: REMOTE_PROPOSAL_OUTCOMES.may_load(deps.storage, *prereq_prop_id)?,
+        });
     }
 
-    let mut max_votes = 0;
-    let mut max_index = 0;
-    for (i, votes) in option_votes.iter().enumerate() {
-        if *votes > max_votes {
-            max_votes = *votes;
-            max_index = i;
+    let (tally, total_power) = combine_tallies(&prop.aggregation_strategy, &sources)?;
+    let outcome = EXECUTED_PROPOSALS.may_load(deps.storage, prop_id)?;
+
+    Ok(QueryTallyResponse {
+        tally,
+        total_power,
+        outcome,
+        sources,
+    })
+}
+
+// Tallies the votes cast on a proposal, locally and relayed from its remote voting chains.
+fn local_tally(
+    deps: Deps,
+    prop: &Proposal,
+    local_total_power: u64,
+) -> Result<SourceTally, ContractError> {
+    let mut option_votes = vec![0; prop.options.len()];
+    let mut total_power = local_total_power;
+
+    let mut count_vote = |power: u64, options: &[(String, Decimal)]| {
+        for (option, weight) in options {
+            let option_index = prop.options.iter().position(|x| &x.name == option).unwrap();
+            // the power for each option is rounded down
+            option_votes[option_index] =
+                add_power(option_votes[option_index], weighted_power(power, *weight)?)?;
         }
+        Ok::<_, ContractError>(())
+    };
+
+    let votes = VOTE_MAP
+        .prefix(prop.id)
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (_, vote) in votes {
+        count_vote(vote.power, &vote.options)?;
     }
 
-    Ok(prop
+    // remote votes are normalized like the results of prerequisite remote proposals
+    for voting_chain in prop.remote_voting_chains.iter() {
+        let remote_total_power = REMOTE_VOTING_TOTAL_POWER
+            .may_load(deps.storage, (prop.id, voting_chain.chain.clone()))?
+            .unwrap_or(0);
+        let normalize = |power: u64| {
+            voting_chain
+                .normalization
+                .apply(power, remote_total_power, local_total_power)
+        };
+
+        let votes = REMOTE_VOTES
+            .prefix((prop.id, voting_chain.chain.clone()))
+            .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (_, vote) in votes {
+            count_vote(normalize(vote.power)?, &vote.options)?;
+        }
+        total_power = add_power(total_power, normalize(remote_total_power)?)?;
+    }
+
+    let tally = prop
         .options
         .iter()
         .zip(option_votes.iter())
-        .map(|(option, votes)| (option.clone(), *votes))
-        .collect::<Vec<_>>())
+        .map(|(option, votes)| (option.name.clone(), *votes))
+        .collect::<Vec<_>>();
+
+    Ok(SourceTally {
+        chain: None,
+        prop_id: prop.id,
+        tally,
+        total_power,
+        outcome: None,
+    })
+}
+
+// Combines the local tally, which comes first, with the tallies of the remote proposals into the
+// tally and total power of the proposal.
+fn combine_tallies(
+    strategy: &AggregationStrategy,
+    sources: &[SourceTally],
+) -> Result<(Vec<(String, u64)>, u64), ContractError> {
+    let local = &sources[0];
+    let mut tally = local.tally.clone();
+    let mut total_power = local.total_power;
+
+    // remote votes do not count toward the tally when remote chains can only veto
+    if *strategy == AggregationStrategy::RemoteVeto {
+        return Ok((tally, total_power));
+    }
+
+    for remote in sources[1..].iter() {
+        let weight = match strategy {
+            AggregationStrategy::WeightedSum { weights } => weights
+                .iter()
+                .find(|(chain, _)| Some(chain) == remote.chain.as_ref())
+                .map(|(_, weight)| *weight)
+                .unwrap_or(Decimal::one()),
+            _ => Decimal::one(),
+        };
+        for (index, (_, votes)) in remote.tally.iter().enumerate() {
+            tally[index].1 = add_power(tally[index].1, weighted_power(*votes, weight)?)?;
+        }
+        total_power = add_power(total_power, weighted_power(remote.total_power, weight)?)?;
+    }
+    Ok((tally, total_power))
+}
+
+// Multiplies power by a weight, rounding down.
+fn weighted_power(power: u64, weight: Decimal) -> Result<u64, ContractError> {
+    let weighted = Uint128::from(power)
+        .checked_mul_floor(weight)
+        .map_err(|_| ContractError::PowerOverflow {})?;
+    u64::try_from(weighted.u128()).map_err(|_| ContractError::PowerOverflow {})
+}
+
+fn add_power(power: u64, other: u64) -> Result<u64, ContractError> {
+    power
+        .checked_add(other)
+        .ok_or(ContractError::PowerOverflow {})
+}
+
+// Determines the outcome of a proposal from its tally according to its aggregation strategy.
+pub fn proposal_outcome(
+    config: &Config,
+    prop: &Proposal,
+    tally: &QueryTallyResponse,
+) -> Result<String, ContractError> {
+    let local = &tally.sources[0];
+    let remotes = &tally.sources[1..];
+
+    match prop.aggregation_strategy {
+        AggregationStrategy::Sum | AggregationStrategy::WeightedSum { .. } => {
+            tally_outcome(config, prop, &tally.tally, tally.total_power)
+        }
+        AggregationStrategy::RemoteVeto => {
+            if remotes
+                .iter()
+                .any(|remote| remote.outcome.as_deref() == Some(VETOED_OUTCOME))
+            {
+                return Ok(VETOED_OUTCOME.to_string());
+            }
+            tally_outcome(config, prop, &local.tally, local.total_power)
+        }
+        AggregationStrategy::AllMustPass => {
+            let outcome = tally_outcome(config, prop, &local.tally, local.total_power)?;
+            if prop.option(&outcome).is_some()
+                && remotes
+                    .iter()
+                    .any(|remote| remote.outcome.as_ref() != Some(&outcome))
+            {
+                return Ok(THRESHOLD_NOT_REACHED_OUTCOME.to_string());
+            }
+            Ok(outcome)
+        }
+        AggregationStrategy::LocalOnlyWithRemoteQuorum => {
+            if tally_outcome(config, prop, &tally.tally, tally.total_power)?
+                == FAILED_QUORUM_OUTCOME
+            {
+                return Ok(FAILED_QUORUM_OUTCOME.to_string());
+            }
+            // the quorum was checked with the remote votes
+            let config = Config {
+                quorum: Decimal::zero(),
+                ..config.clone()
+            };
+            tally_outcome(&config, prop, &local.tally, local.total_power)
+        }
+    }
 }
 
 pub fn module_ibc_handler(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     app: CCGovApp,
     msg: ModuleIbcMsg,
 ) -> Result<Response, ContractError> {
-    println!("Module IBC Handler: {:?}", msg);
-    let wrapped_msg = from_json(msg.msg)?;
-    match wrapped_msg {
-        QueryMsg::Module(CCGovQueryMsg::QueryTally { prop_id }) => {
-            // check that the proposal was executed
-            let executed_props = EXECUTED_PROPOSALS.load(deps.storage)?;
-            if !executed_props.iter().any(|(id, _)| *id == prop_id) {
-                return Err(ContractError::ProposalNotExecuted {});
+    // only trusted modules on other chains can send messages
+    if !TRUSTED_MODULES.has(
+        deps.storage,
+        (msg.client_chain.to_string(), msg.source_module.id()),
+    ) {
+        return Err(ContractError::UnauthorizedIbcMessage {});
+    }
+
+    // messages sent by ccgov instances on other chains
+    if let Ok(ibc_msg) = from_json::<CCGovIbcMessage>(&msg.msg) {
+        // other trusted modules can query tallies, but only ccgov can change state
+        if !matches!(ibc_msg, CCGovIbcMessage::QueryTally { .. })
+            && msg.source_module.id() != CCGOV_ID
+        {
+            return Err(ContractError::UnauthorizedIbcMessage {});
+        }
+        return match ibc_msg {
+            CCGovIbcMessage::QueryTally { prop_id } => {
+                module_ibc_tally(deps.as_ref(), &app, prop_id)
             }
+            CCGovIbcMessage::Subscribe {
+                prop_id,
+                parent_prop_id,
+                remote_prop_id,
+                contract_addr,
+            } => {
+                // the subscriber needs to refer to a proposal of this contract
+                if contract_addr != env.contract.address.as_str() {
+                    return Err(ContractError::UnauthorizedIbcMessage {});
+                }
+                if PROP_MAP.load(deps.storage, prop_id)?.status == ProposalStatus::Cancelled {
+                    return Err(ContractError::ProposalCancelled {});
+                }
+
+                let chain = msg.client_chain.to_string();
+                let key = (prop_id, chain.clone(), remote_prop_id);
+                if !SUBSCRIBERS.has(deps.storage, key.clone())
+                    && SUBSCRIBERS
+                        .sub_prefix(prop_id)
+                        .keys(deps.storage, None, None, Order::Ascending)
+                        .take(MAX_SUBSCRIBERS)
+                        .count()
+                        >= MAX_SUBSCRIBERS
+                {
+                    return Err(ContractError::TooManySubscribers {
+                        max: MAX_SUBSCRIBERS,
+                    });
+                }
+                SUBSCRIBERS.save(deps.storage, key, &parent_prop_id)?;
+
+                let mut response = app
+                    .response("subscribe")
+                    .add_attribute("prop_id", prop_id.to_string())
+                    .add_attribute("subscriber", chain.clone());
+
+                // the result of a proposal that was already executed is sent right away
+                if EXECUTED_PROPOSALS.has(deps.storage, prop_id) {
+                    let result = CCGovIbcMessage::ProposalResult {
+                        prop_id,
+                        parent_prop_id,
+                        remote_prop_id,
+                        tally: query_tally(deps.as_ref(), prop_id)?,
+                        contract_addr: env.contract.address.to_string(),
+                    };
+                    response = response.add_message(proposal_result_msg(
+                        deps.as_ref(),
+                        &app,
+                        chain,
+                        result,
+                    )?);
+                }
+                Ok(response)
+            }
+            CCGovIbcMessage::ProposalResult {
+                prop_id,
+                parent_prop_id,
+                remote_prop_id,
+                tally,
+                contract_addr,
+            } => {
+                // the result needs to come from the ccgov, chain and proposal that the remote
+                // proposal refers to, and be for a proposal that was executed
+                let prereq = REMOTE_PROPOSALS.load(deps.storage, remote_prop_id)?;
+                let parent_prop = PROP_MAP.load(deps.storage, parent_prop_id)?;
+                if prereq.prop_id != prop_id
+                    || prereq.chain != msg.client_chain.to_string()
+                    || prereq.contract_addr != contract_addr
+                    || !parent_prop.prereq_proposals.contains(&remote_prop_id)
+                    || tally.outcome.is_none()
+                {
+                    return Err(ContractError::UnauthorizedIbcMessage {});
+                }
 
-            let tally = query_tally(deps.as_ref(), prop_id)?;
+                resolve_remote_proposal(deps.storage, parent_prop_id, remote_prop_id, &tally)?;
 
-            let query_tally_response = QueryTallyResponse { tally };
+                let response = app
+                    .response("proposal_result")
+                    .add_attribute("prop_id", parent_prop_id.to_string())
+                    .add_attribute("remote_prop_id", remote_prop_id.to_string());
+                auto_execute(deps, &env, parent_prop_id, response)
+            }
+            CCGovIbcMessage::RemoteVote {
+                prop_id,
+                voter,
+                options,
+            } => {
+                // votes are only accepted from the remote voting chains of the proposal
+                let chain = msg.client_chain.to_string();
+                let prop = PROP_MAP.load(deps.storage, prop_id)?;
+                let voting_chain = prop
+                    .remote_voting_chains
+                    .iter()
+                    .find(|voting_chain| voting_chain.chain == chain)
+                    .ok_or(ContractError::UnauthorizedIbcMessage {})?;
+
+                assert_voting_open(&env, &prop)?;
+                assert_valid_vote_options(&prop, &options)?;
+                if !CONFIG.load(deps.storage)?.allow_revoting
+                    && REMOTE_VOTES.has(deps.storage, (prop_id, chain.clone(), voter.clone()))
+                {
+                    return Err(ContractError::AlreadyVoted {});
+                }
 
-            Ok(app
-                .response("module_ibc")
-                .set_data(to_json_binary(&query_tally_response)?))
+                // the vote is recorded once the power of the voter on the remote chain arrives
+                let ibc_client = app.ibc_client(deps.as_ref());
+                let power_query = WasmQuery::Smart {
+                    contract_addr: voting_chain.power_contract.clone(),
+                    msg: to_json_binary(&PowerContractQueryMsg::GetVotingPowerMsg(
+                        GetVotingPowerMsg {
+                            voter: voter.clone(),
+                            time: Some(prop.start_time),
+                        },
+                    ))?,
+                };
+                let remote_vote_msg = RemoteVoteMsg {
+                    prop_id,
+                    chain: chain.clone(),
+                    voter: voter.clone(),
+                    options,
+                };
+                let query_msg = ibc_client.ibc_query(
+                    chain.clone(),
+                    power_query,
+                    CallbackInfo::new(
+                        REMOTE_VOTE_CALLBACK_ID,
+                        Some(to_json_binary(&remote_vote_msg)?),
+                    ),
+                )?;
+
+                Ok(app
+                    .response("remote_vote")
+                    .add_attribute("prop_id", prop_id.to_string())
+                    .add_attribute("chain", chain)
+                    .add_attribute("voter", voter)
+                    .add_message(query_msg)
+                    .add_messages(remote_total_power_msgs(deps.as_ref(), &app, &prop)?))
+            }
+        };
+    }
+
+    let wrapped_msg = from_json(msg.msg).map_err(|_| ContractError::UnknownIbcMessage {})?;
+    match wrapped_msg {
+        QueryMsg::Module(CCGovQueryMsg::QueryTally { prop_id }) => {
+            module_ibc_tally(deps.as_ref(), &app, prop_id)
         }
 
-        _ => panic!("Unknown message"),
+        _ => Err(ContractError::UnknownIbcMessage {}),
+    }
+}
+
+// Answers a tally request from another chain. Only executed proposals have a final tally.
+fn module_ibc_tally(deps: Deps, app: &CCGovApp, prop_id: u64) -> CCGovResult {
+    // cancelled proposals have no result
+    if PROP_MAP.load(deps.storage, prop_id)?.status == ProposalStatus::Cancelled {
+        return Err(ContractError::ProposalCancelled {});
+    }
+
+    // check that the proposal was executed
+    if !EXECUTED_PROPOSALS.has(deps.storage, prop_id) {
+        return Err(ContractError::ProposalNotExecuted {});
+    }
+
+    let query_tally_response = query_tally(deps, prop_id)?;
+
+    Ok(app
+        .response("module_ibc")
+        .set_data(to_json_binary(&query_tally_response)?))
+}
+
+// Stores the tally of an executed remote proposal. Once all prerequisites of the parent proposal
+// are resolved, it can be executed.
+fn resolve_remote_proposal(
+    storage: &mut dyn Storage,
+    parent_prop_id: u64,
+    remote_prop_id: u64,
+    remote_tally: &QueryTallyResponse,
+) -> StdResult<()> {
+    for (option, votes) in remote_tally.tally.iter() {
+        REMOTE_PROPOSALS_TALLIES.save(storage, (remote_prop_id, option.clone()), votes)?;
+    }
+
+    REMOTE_PROPOSALS_TOTAL_POWER.save(storage, remote_prop_id, &remote_tally.total_power)?;
+    if let Some(outcome) = &remote_tally.outcome {
+        REMOTE_PROPOSAL_OUTCOMES.save(storage, remote_prop_id, outcome)?;
+    }
+    REMOTE_PROPOSAL_RESOLVED.save(storage, remote_prop_id, &true)?;
+
+    update_pending_remote(storage, parent_prop_id)
+}
+
+// Whether the results of all prerequisite remote proposals and the total power of all remote
+// voting chains of a proposal are known, which its tally needs.
+fn remote_results_known(storage: &dyn Storage, prop: &Proposal) -> bool {
+    let all_resolved = prop.prereq_proposals.iter().all(|prereq_prop_id| {
+        REMOTE_PROPOSAL_RESOLVED
+            .load(storage, *prereq_prop_id)
+            .unwrap_or(false)
+    });
+    let all_total_powers_known = prop.remote_voting_chains.iter().all(|voting_chain| {
+        REMOTE_VOTING_TOTAL_POWER.has(storage, (prop.id, voting_chain.chain.clone()))
+    });
+    all_resolved && all_total_powers_known
+}
+
+// A proposal that waits for remote results can be executed once all of them are known.
+fn update_pending_remote(storage: &mut dyn Storage, prop_id: u64) -> StdResult<()> {
+    let mut prop = PROP_MAP.load(storage, prop_id)?;
+    if prop.status == ProposalStatus::PendingRemote && remote_results_known(storage, &prop) {
+        prop.status = ProposalStatus::PendingExecution;
+        PROP_MAP.save(storage, prop_id, &prop)?;
     }
+    Ok(())
+}
+
+// Executes the parent proposal once all its remote results arrived, if this is enabled in the
+// config and its voting period has ended. The execution runs as a submessage, so a failing
+// execution does not revert the result that was just recorded.
+fn auto_execute(deps: DepsMut, env: &Env, parent_prop_id: u64, response: Response) -> CCGovResult {
+    let prop = PROP_MAP.load(deps.storage, parent_prop_id)?;
+    if !CONFIG.load(deps.storage)?.auto_execute
+        || !remote_results_known(deps.storage, &prop)
+        || current_status(env, &prop) != ProposalStatus::PendingExecution
+    {
+        return Ok(response);
+    }
+
+    let execute_msg = wasm_execute(
+        env.contract.address.to_string(),
+        &ExecuteMsg::from(CCGovExecuteMsg::ExecuteProposal {
+            prop_id: parent_prop_id,
+        }),
+        vec![],
+    )?;
+    Ok(response
+        .add_attribute("auto_execute", parent_prop_id.to_string())
+        .add_submessage(SubMsg::reply_on_error(execute_msg, AUTO_EXECUTE_REPLY_ID)))
+}
+
+// Records why an automatic execution failed. The proposal stays unexecuted, so it can still be
+// executed with ExecuteProposal.
+pub fn auto_execute_reply(_deps: DepsMut, _env: Env, app: CCGovApp, reply: Reply) -> CCGovResult {
+    let error = reply.result.into_result().err().unwrap_or_default();
+    Ok(app
+        .response("auto_execute_failed")
+        .add_attribute("error", error))
 }
 
 pub fn query_tally_callback(
     deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
+    env: Env,
+    _info: MessageInfo,
     app: CCGovApp,
     ibc_msg: IbcResponseMsg,
 ) -> CCGovResult<Response> {
-    let callback_info = ibc_msg.msg.unwrap();
-    match from_json::<RemoteProposalMsg>(&callback_info) {
-        Ok(remote_prop_msg) => {
-            println!("Remote Proposal Msg: {:?}", remote_prop_msg);
-
-            let remote_prop_id = remote_prop_msg.prop_id;
-
-            match ibc_msg.result {
-                CallbackResult::Query { query, result } => {
-                    // get the first result (there should only ever be one at a time)
-                    let unwrapped_res = result.unwrap();
-                    let res = unwrapped_res.get(0).unwrap();
-
-                    // get the tally from the response
-                    let remote_tally = from_json::<QueryTallyResponse>(&res)?;
-
-                    for (option, votes) in remote_tally.tally.iter() {
-                        REMOTE_PROPOSALS_TALLIES.save(
-                            deps.storage,
-                            (remote_prop_id, option.clone()),
-                            &votes,
-                        )?;
-                    }
+    let remote_prop_msg =
+        from_json::<RemoteProposalMsg>(ibc_msg.msg.ok_or(ContractError::IBCError {})?)?;
+    let remote_tally = match ibc_msg.result {
+        CallbackResult::Query {
+            query: _,
+            result: Ok(result),
+        } => {
+            // get the first result (there should only ever be one at a time)
+            let res = result.first().ok_or(ContractError::IBCError {})?;
+            from_json::<QueryTallyResponse>(res)?
+        }
+        _ => return Err(ContractError::IBCError {}),
+    };
+
+    // the remote proposal is only resolved once it was executed there
+    if remote_tally.outcome.is_none() {
+        return Ok(app
+            .response("query_tally_callback")
+            .add_attribute("result", "remote_proposal_not_executed"));
+    }
 
-                    REMOTE_PROPOSAL_RESOLVED.save(deps.storage, remote_prop_id, &true)?;
+    resolve_remote_proposal(
+        deps.storage,
+        remote_prop_msg.parent_prop_id,
+        remote_prop_msg.prop_id,
+        &remote_tally,
+    )?;
 
-                    Ok(app.response("query_tally_callback"))
-                }
-                CallbackResult::Execute {
-                    initiator_msg: _,
-                    result: _,
-                } => Err(ContractError::UnauthorizedIbcMessage {}),
-                CallbackResult::FatalError(_) => Err(ContractError::IBCError {}),
+    let response = app.response("query_tally_callback");
+    auto_execute(deps, &env, remote_prop_msg.parent_prop_id, response)
+}
+
+// Records a vote relayed from a remote voting chain once the power of the voter arrives.
+// Votes that arrive after voting ended are dropped.
+pub fn remote_vote_callback(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    app: CCGovApp,
+    ibc_msg: IbcResponseMsg,
+) -> CCGovResult<Response> {
+    let remote_vote = from_json::<RemoteVoteMsg>(ibc_msg.msg.ok_or(ContractError::IBCError {})?)?;
+    let power = match ibc_msg.result {
+        CallbackResult::Query {
+            query: _,
+            result: Ok(result),
+        } => {
+            let res = result.first().ok_or(ContractError::IBCError {})?;
+            from_json::<GetVotingPowerResponse>(res)?.power
+        }
+        _ => return Err(ContractError::IBCError {}),
+    };
+
+    let RemoteVoteMsg {
+        prop_id,
+        chain,
+        voter,
+        options,
+    } = remote_vote;
+    let response = app
+        .response("remote_vote_callback")
+        .add_attribute("prop_id", prop_id.to_string())
+        .add_attribute("chain", chain.clone())
+        .add_attribute("voter", voter.clone());
+
+    let prop = PROP_MAP.load(deps.storage, prop_id)?;
+    if current_status(&env, &prop) != ProposalStatus::Open {
+        return Ok(response.add_attribute("result", "voting_period_ended"));
+    }
+
+    // another vote of the voter can have arrived in the meantime
+    let previous_vote =
+        REMOTE_VOTES.may_load(deps.storage, (prop_id, chain.clone(), voter.clone()))?;
+    let id = match &previous_vote {
+        Some(_) if !CONFIG.load(deps.storage)?.allow_revoting => {
+            return Ok(response.add_attribute("result", "already_voted"));
+        }
+        Some(previous_vote) => previous_vote.id,
+        None => {
+            let vote_id = VOTE_ID.load(deps.storage)?;
+            VOTE_ID.save(deps.storage, &(vote_id + 1))?;
+            vote_id
+        }
+    };
+
+    let vote = Vote {
+        id,
+        prop_id,
+        voter: voter.clone(),
+        power,
+        options,
+    };
+    REMOTE_VOTES.save(deps.storage, (prop_id, chain, voter), &vote)?;
+
+    Ok(response
+        .add_attribute("result", "vote_recorded")
+        .add_attribute("vote_id", id.to_string())
+        .add_attribute("power", power.to_string())
+        .add_attribute("options", format_vote_options(&vote.options)))
+}
+
+// Stores the total power of a remote voting chain of a proposal.
+pub fn remote_total_power_callback(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    app: CCGovApp,
+    ibc_msg: IbcResponseMsg,
+) -> CCGovResult<Response> {
+    let RemoteTotalPowerMsg {
+        prop_id,
+        chain,
+        time,
+    } = from_json(ibc_msg.msg.ok_or(ContractError::IBCError {})?)?;
+    let total_power = match ibc_msg.result {
+        CallbackResult::Query {
+            query: _,
+            result: Ok(result),
+        } => {
+            let res = result.first().ok_or(ContractError::IBCError {})?;
+            from_json::<GetTotalPowerResponse>(res)?.power
+        }
+        _ => return Err(ContractError::IBCError {}),
+    };
+
+    // the start time may have moved since the query was sent, when the deposit was reached late
+    if PROP_MAP.load(deps.storage, prop_id)?.start_time != time {
+        return Ok(app
+            .response("remote_total_power_callback")
+            .add_attribute("prop_id", prop_id.to_string())
+            .add_attribute("chain", chain)
+            .add_attribute("result", "outdated"));
+    }
+    REMOTE_VOTING_TOTAL_POWER.save(deps.storage, (prop_id, chain.clone()), &total_power)?;
+    update_pending_remote(deps.storage, prop_id)?;
+
+    let response = app
+        .response("remote_total_power_callback")
+        .add_attribute("prop_id", prop_id.to_string())
+        .add_attribute("chain", chain)
+        .add_attribute("total_power", total_power.to_string());
+    auto_execute(deps, &env, prop_id, response)
+}
+
+// Records whether a prerequisite proposal exists on its chain and has the same options as the
+// parent proposal. A parent proposal with an invalid prerequisite is cancelled and its deposits
+// are refunded.
+pub fn validate_prereq_callback(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    app: CCGovApp,
+    ibc_msg: IbcResponseMsg,
+) -> CCGovResult<Response> {
+    let RemoteProposalMsg {
+        parent_prop_id,
+        prop_id: remote_prop_id,
+        ..
+    } = from_json(ibc_msg.msg.ok_or(ContractError::IBCError {})?)?;
+    let mut parent_prop = PROP_MAP.load(deps.storage, parent_prop_id)?;
+
+    let validation = match ibc_msg.result {
+        CallbackResult::Query {
+            query: _,
+            result: Ok(result),
+        } => {
+            let res = result.first().ok_or(ContractError::IBCError {})?;
+            let remote_prop = from_json::<QueryProposalResponse>(res)?.prop;
+            // tallies are matched by option name, so both proposals need the same options
+            let same_options = remote_prop.options.len() == parent_prop.options.len()
+                && parent_prop
+                    .options
+                    .iter()
+                    .all(|option| remote_prop.option(&option.name).is_some());
+            if same_options {
+                PrerequisiteValidation::Valid
+            } else {
+                PrerequisiteValidation::IncompatibleOptions
             }
         }
-        Err(_) => Err(ContractError::IBCError {}),
+        // the query fails if there is no such proposal at the contract
+        CallbackResult::Query {
+            query: _,
+            result: Err(_),
+        } => PrerequisiteValidation::NotFound,
+        _ => return Err(ContractError::IBCError {}),
+    };
+    REMOTE_PROPOSAL_VALIDATIONS.save(deps.storage, remote_prop_id, &validation)?;
+
+    let response = app
+        .response("validate_prereq_callback")
+        .add_attribute("prop_id", parent_prop_id.to_string())
+        .add_attribute("remote_prop_id", remote_prop_id.to_string());
+    if validation == PrerequisiteValidation::Valid {
+        return Ok(response.add_attribute("result", "valid"));
+    }
+    let reason = match validation {
+        PrerequisiteValidation::NotFound => "not_found",
+        _ => "incompatible_options",
+    };
+    let response = response.add_attribute("result", reason);
+
+    // a proposal that was already cancelled or executed is only flagged
+    if parent_prop.status == ProposalStatus::Cancelled
+        || EXECUTED_PROPOSALS.has(deps.storage, parent_prop_id)
+    {
+        return Ok(response);
     }
+
+    parent_prop.status = ProposalStatus::Cancelled;
+    PROP_MAP.save(deps.storage, parent_prop_id, &parent_prop)?;
+
+    Ok(response
+        .add_event(
+            Event::new("invalid_prerequisite")
+                .add_attribute("prop_id", parent_prop_id.to_string())
+                .add_attribute("remote_prop_id", remote_prop_id.to_string())
+                .add_attribute("reason", reason),
+        )
+        .add_messages(refund_deposit_msgs(deps.as_ref(), &parent_prop)?))
 }
@@ -16,7 +16,7 @@ pub enum ContractError {
     #[error("Power contract not whitelisted")]
     PowerContractNotWhitelisted {},
 
-    #[error("Voting period for proposal has not ended yet")]
+    #[error("Voting period for proposal has not ended yet and its outcome is not decided")]
     VotingPeriodNotEnded {},
 
     #[error("Voting period for proposal has ended")]
@@ -34,6 +34,54 @@ pub enum ContractError {
     #[error("{0}")]
     DappError(#[from] AppError),
 
+    #[error("Vote weights must be positive and sum to 1")]
+    InvalidVoteWeights {},
+
+    #[error("Cannot delegate to yourself")]
+    SelfDelegation {},
+
+    #[error("Sender has not delegated their power")]
+    NotDelegated {},
+
+    #[error("Delegate already has the maximum of {max} delegators")]
+    TooManyDelegators { max: usize },
+
+    #[error("Voting power overflow")]
+    PowerOverflow {},
+
+    #[error("Normalization factors must be positive and at most {max}, caps must be positive")]
+    InvalidNormalization { max: u64 },
+
+    #[error("Aggregation weights must be positive and given once for each prerequisite chain")]
+    InvalidAggregationWeights {},
+
+    #[error("Proposal already has the maximum of {max} subscribers")]
+    TooManySubscribers { max: usize },
+
+    #[error("Deposit can only contain denoms of the minimum deposit")]
+    InvalidDeposit {},
+
+    #[error("Proposal is not in its deposit period")]
+    NotInDepositPeriod {},
+
+    #[error("Deposit period for proposal has not ended yet")]
+    DepositPeriodNotEnded {},
+
+    #[error("Voting period for proposal has not started yet")]
+    VotingNotStarted {},
+
+    #[error("Proposal was cancelled")]
+    ProposalCancelled {},
+
+    #[error("Voting period is outside of the bounds from the config")]
+    InvalidVotingPeriod {},
+
+    #[error("Only proposals that never expire can be closed")]
+    CannotCloseProposal {},
+
+    #[error("Start time must not be in the past")]
+    InvalidStartTime {},
+
     #[error("Voter has already voted")]
     AlreadyVoted {},
 
@@ -43,9 +91,24 @@ pub enum ContractError {
     #[error("Unauthorized IBC message")]
     UnauthorizedIbcMessage {},
 
+    #[error("Prerequisite proposal is not on a trusted ccgov instance")]
+    UntrustedPrerequisite {},
+
+    #[error("Unknown IBC message")]
+    UnknownIbcMessage {},
+
     #[error("Error while executing IBC message")]
     IBCError {},
 
     #[error("Proposal not executed yet")]
     ProposalNotExecuted {},
+
+    #[error("Proposal was already executed")]
+    ProposalAlreadyExecuted {},
+
+    #[error("Invalid config: {reason}")]
+    InvalidConfig { reason: String },
+
+    #[error("No power contract given and no default power contract configured")]
+    NoPowerContract {},
 }
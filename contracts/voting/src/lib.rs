@@ -3,7 +3,8 @@ mod error;
 pub mod helpers;
 pub mod msg;
 pub mod state;
-pub mod testing;
+#[cfg(test)]
+mod testing;
 
 pub use crate::error::ContractError;
 
@@ -16,3 +17,8 @@ pub const CCGOV_NAME: &str = "ccgov";
 pub const CCGOV_ID: &str = const_format::formatcp!("{CCGOV_NAMESPACE}:{CCGOV_NAME}");
 
 pub const QUERY_TALLY_CALLBACK_ID: &str = "query_tally_callback";
+pub const REMOTE_VOTE_CALLBACK_ID: &str = "remote_vote_callback";
+pub const REMOTE_TOTAL_POWER_CALLBACK_ID: &str = "remote_total_power_callback";
+pub const VALIDATE_PREREQ_CALLBACK_ID: &str = "validate_prereq_callback";
+
+pub const AUTO_EXECUTE_REPLY_ID: u64 = 1;
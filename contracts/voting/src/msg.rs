@@ -1,6 +1,11 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Coin, Decimal, Timestamp};
+use cw_utils::Expiration;
 
-use crate::state::{Proposal, Vote};
+use crate::state::{
+    AggregationStrategy, Config, PrerequisiteProposal, PrerequisiteValidation, Proposal,
+    ProposalOption, ProposalStatus, RemoteVotingChain, Vote,
+};
 
 use crate::contract::CCGovApp;
 
@@ -8,20 +13,72 @@ use crate::contract::CCGovApp;
 abstract_app::app_msg_types!(CCGovApp, CCGovExecuteMsg, CCGovQueryMsg);
 
 #[cw_serde]
-pub struct CCGovInstantiateMsg {}
+pub struct CCGovInstantiateMsg {
+    // The voting period of proposals that do not specify one, in minutes.
+    pub voting_period_in_minutes: u64,
+    // The bounds for the voting period of proposals, in minutes.
+    pub min_voting_period_in_minutes: u64,
+    pub max_voting_period_in_minutes: u64,
+    // The power contract used by proposals that do not specify one.
+    pub default_power_contract: Option<String>,
+    // The fraction of the total power that needs to vote for a proposal to be valid.
+    pub quorum: Decimal,
+    // The fraction of the votes cast that the winning option needs to exceed.
+    pub threshold: Decimal,
+    // The fraction of the votes cast for a NoWithVeto option above which a proposal is vetoed.
+    pub veto_threshold: Decimal,
+    // Whether voters can change their vote while the proposal is open.
+    pub allow_revoting: bool,
+    // Whether a proposal is executed as soon as the last result of its prerequisite remote
+    // proposals arrives, if its voting period has ended. Otherwise it needs another ExecuteProposal.
+    pub auto_execute: bool,
+    // The deposit a proposal needs before voting starts. If empty, proposals need no deposit.
+    pub min_deposit: Vec<Coin>,
+    // How long others can top up the deposit of a proposal, in minutes.
+    pub deposit_period_in_minutes: u64,
+    // Receives the deposits of vetoed proposals and proposals that failed the quorum.
+    // If not set, these deposits are burned.
+    pub deposit_recipient: Option<String>,
+    // The admin of the contract. If not set, the config can only be changed by governance.
+    pub admin: Option<String>,
+}
 
 #[cw_serde]
 #[derive(cw_orch::ExecuteFns)]
 #[impl_into(ExecuteMsg)]
 pub enum CCGovExecuteMsg {
-    // Create a new proposal.
+    // Create a new proposal. The attached funds are the initial deposit.
+    #[payable]
     CreateProposal {
         title: String,
         description: String,
-        power_contract_addr: String,
-        options: Vec<String>,
-        // proposal id on remote chain, remote chain id, remote contract address
-        prereq_proposals: Vec<(u64, String, String)>,
+        // The power contract of the proposal. Defaults to the power contract from the config.
+        power_contract_addr: Option<String>,
+        // The options that can be voted on, each with the messages to dispatch if it wins.
+        options: Vec<ProposalOption>,
+        // proposals on other chains whose results count toward this proposal
+        prereq_proposals: Vec<PrerequisiteProposal>,
+        // The voting period of the proposal, in minutes. Defaults to the voting period from the config.
+        voting_period_in_minutes: Option<u64>,
+        // When voting starts. Defaults to the creation of the proposal, or the moment the minimum
+        // deposit is reached. Lets proposals on several chains open and close at the same time.
+        start_time: Option<Timestamp>,
+        // When voting ends, as a block height, a time or never. Proposals that never expire need
+        // to be closed with CloseProposal. Cannot be combined with voting_period_in_minutes.
+        expiration: Option<Expiration>,
+        // How the results of the prerequisite proposals are combined with the local votes.
+        // Defaults to summing them.
+        aggregation_strategy: Option<AggregationStrategy>,
+        // Chains whose voters can vote directly on the proposal with RemoteVote. Defaults to none.
+        remote_voting_chains: Option<Vec<RemoteVotingChain>>,
+        // Whether to check with an IBC query that each prerequisite proposal exists and has the
+        // same options. The proposal is cancelled if a prerequisite is invalid. Defaults to false.
+        validate_prereqs: Option<bool>,
+    },
+    // Add the attached funds to the deposit of a proposal in its deposit period.
+    #[payable]
+    Deposit {
+        prop_id: u64,
     },
     // Execute a proposal for which the voting period has ended.
     ExecuteProposal {
@@ -32,13 +89,116 @@ pub enum CCGovExecuteMsg {
         prop_id: u64,
         option: String,
     },
+    // Vote on a proposal, splitting the power across several options.
+    // The weights need to be positive and sum to 1.
+    WeightedVote {
+        prop_id: u64,
+        options: Vec<(String, Decimal)>,
+    },
+    // Vote on a proposal on another chain that accepts votes from this chain. The vote is relayed
+    // by ccgov, and the power of the sender is queried from the power contract of this chain
+    // that the proposal names.
+    RemoteVote {
+        chain: String,
+        prop_id: u64,
+        options: Vec<(String, Decimal)>,
+    },
+    // Replace the config. The new config is validated like the instantiate message.
+    // Can only be called by the admin or governance.
+    UpdateConfig {
+        config: CCGovInstantiateMsg,
+    },
+    // Add a power contract to the whitelist. Can only be called by the admin or governance.
+    AddPowerContract {
+        power_contract_addr: String,
+    },
+    // Remove a power contract from the whitelist. Can only be called by the admin or governance.
+    RemovePowerContract {
+        power_contract_addr: String,
+    },
+    // Add a power contract on another chain to the whitelist of remote voting chains.
+    // Can only be called by the admin or governance.
+    AddRemotePowerContract {
+        chain: String,
+        power_contract_addr: String,
+    },
+    // Remove a power contract on another chain from the whitelist. Can only be called by the admin or governance.
+    RemoveRemotePowerContract {
+        chain: String,
+        power_contract_addr: String,
+    },
+    // Trust a module on another chain to send module IBC messages, e.g. ccgov on that chain.
+    // Can only be called by the admin or governance.
+    AddTrustedModule {
+        chain: String,
+        module_id: String,
+    },
+    // Stop trusting a module on another chain. Can only be called by the admin or governance.
+    RemoveTrustedModule {
+        chain: String,
+        module_id: String,
+    },
+    // Trust the ccgov instance at the given address on another chain, so proposals can use its
+    // proposals as prerequisites. Can only be called by the admin or governance.
+    AddTrustedInstance {
+        chain: String,
+        contract_addr: String,
+    },
+    // Stop trusting a ccgov instance on another chain. Can only be called by the admin or governance.
+    RemoveTrustedInstance {
+        chain: String,
+        contract_addr: String,
+    },
+    // Delegate the sender's voting power to another address. Replaces an existing delegation.
+    Delegate {
+        to: String,
+    },
+    // Remove the sender's delegation.
+    Undelegate {},
+    // Cancel a proposal and refund its deposits. The proposer can cancel it as long as no votes
+    // were cast, the admin or governance at any time before it is executed.
+    CancelProposal {
+        prop_id: u64,
+    },
+    // End the voting period of a proposal that never expires. Can only be called by the admin
+    // or governance.
+    CloseProposal {
+        prop_id: u64,
+    },
 }
 
 #[non_exhaustive]
 #[cosmwasm_schema::cw_serde]
 pub enum CCGovIbcMessage {
     // Route a message
-    QueryTally { prop_id: u64 },
+    QueryTally {
+        prop_id: u64,
+    },
+    // Sent by a chain that has the proposal as a prerequisite of one of its own proposals.
+    // Once the proposal is executed, its tally is sent back with a ProposalResult.
+    Subscribe {
+        prop_id: u64,
+        // the proposal on the subscribing chain and its id for the remote proposal
+        parent_prop_id: u64,
+        remote_prop_id: u64,
+        // the address of ccgov that the subscribing chain expects the proposal on
+        contract_addr: String,
+    },
+    // The tally of an executed proposal, sent to the chains that subscribed to it.
+    ProposalResult {
+        prop_id: u64,
+        parent_prop_id: u64,
+        remote_prop_id: u64,
+        tally: QueryTallyResponse,
+        // the address of the sending ccgov
+        contract_addr: String,
+    },
+    // A vote on a proposal that accepts votes from the sending chain, cast by a voter there.
+    RemoteVote {
+        prop_id: u64,
+        voter: String,
+        options: Vec<(String, Decimal)>,
+    },
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -62,6 +222,130 @@ pub enum CCGovQueryMsg {
 
     #[returns(QueryTallyResponse)]
     QueryTally { prop_id: u64 },
+
+    // The prerequisite proposals of a proposal, with whether they were checked and resolved.
+    #[returns(QueryPrerequisitesResponse)]
+    QueryPrerequisites { prop_id: u64 },
+
+    #[returns(ConfigResponse)]
+    Config {},
+
+    #[returns(ListPowerContractsResponse)]
+    ListPowerContracts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // Lists the whitelisted power contracts on other chains as (chain, address), ordered by chain.
+    #[returns(ListRemotePowerContractsResponse)]
+    ListRemotePowerContracts {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+
+    // Lists the trusted modules on other chains as (chain, module id), ordered by chain.
+    #[returns(ListTrustedModulesResponse)]
+    ListTrustedModules {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+
+    // Lists the trusted ccgov instances on other chains as (chain, address), ordered by chain.
+    #[returns(ListTrustedInstancesResponse)]
+    ListTrustedInstances {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+
+    // Lists proposals in ascending id order, optionally only those with the given status.
+    #[returns(ListProposalsResponse)]
+    ListProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        status: Option<ProposalStatus>,
+    },
+
+    // Lists the votes on a proposal in ascending voter order.
+    #[returns(ListVotesResponse)]
+    ListVotes {
+        prop_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // Lists the votes of a voter in ascending proposal id order.
+    #[returns(ListVotesResponse)]
+    ListVotesByVoter {
+        voter: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(DelegationResponse)]
+    Delegation { delegator: String },
+
+    // Lists the addresses that delegated to the delegate in ascending order.
+    #[returns(ListDelegatorsResponse)]
+    ListDelegators {
+        delegate: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // The power delegated to the delegate on a proposal that the delegate can still vote with,
+    // i.e. of delegators that have not voted themselves or through another delegate.
+    #[returns(DelegatedPowerResponse)]
+    DelegatedPower { delegate: String, prop_id: u64 },
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListPowerContractsResponse {
+    pub power_contracts: Vec<String>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListRemotePowerContractsResponse {
+    pub remote_power_contracts: Vec<(String, String)>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListTrustedModulesResponse {
+    pub trusted_modules: Vec<(String, String)>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListTrustedInstancesResponse {
+    pub trusted_instances: Vec<(String, String)>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct DelegationResponse {
+    pub delegate: Option<String>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListDelegatorsResponse {
+    pub delegators: Vec<String>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct DelegatedPowerResponse {
+    pub power: u64,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListProposalsResponse {
+    pub proposals: Vec<Proposal>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct ListVotesResponse {
+    pub votes: Vec<Vote>,
 }
 
 #[cosmwasm_schema::cw_serde]
@@ -74,10 +358,19 @@ pub struct QueryVoteResponse {
     pub vote: Vote,
 }
 
+// The queries that a power contract needs to answer.
+#[cosmwasm_schema::cw_serde]
+pub enum PowerContractQueryMsg {
+    GetVotingPowerMsg(GetVotingPowerMsg),
+    GetTotalPowerMsg(GetTotalPowerMsg),
+}
+
 // The message that needs to be sent to the power contract to get the voting power of a voter.
 #[cosmwasm_schema::cw_serde]
 pub struct GetVotingPowerMsg {
     pub voter: String,
+    // The power is queried as of this time. If not set, the current power is returned.
+    pub time: Option<Timestamp>,
 }
 
 // The response to a GetVotingPowerMsg to a power contract needs to have this form.
@@ -86,6 +379,19 @@ pub struct GetVotingPowerResponse {
     pub power: u64,
 }
 
+// The message that needs to be sent to the power contract to get the total voting power.
+#[cosmwasm_schema::cw_serde]
+pub struct GetTotalPowerMsg {
+    // The power is queried as of this time. If not set, the current power is returned.
+    pub time: Option<Timestamp>,
+}
+
+// The response to a GetTotalPowerMsg to a power contract needs to have this form.
+#[cosmwasm_schema::cw_serde]
+pub struct GetTotalPowerResponse {
+    pub power: u64,
+}
+
 #[cosmwasm_schema::cw_serde]
 pub struct QueryTotalVotedPowerResponse {
     pub power: u64,
@@ -98,13 +404,68 @@ pub struct QueryExecutedProposalsResponse {
 
 #[cosmwasm_schema::cw_serde]
 pub struct QueryTallyResponse {
+    // option, num_votes, combined according to the aggregation strategy of the proposal
+    pub tally: Vec<(String, u64)>,
+    // the total power that could have voted on the proposal, including remote proposals
+    pub total_power: u64,
+    // the outcome of the proposal, once it was executed
+    pub outcome: Option<String>,
+    // the votes cast on the proposal, including the normalized votes relayed from remote chains,
+    // followed by the normalized results of each prerequisite remote proposal
+    pub sources: Vec<SourceTally>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct QueryPrerequisitesResponse {
+    pub prerequisites: Vec<PrerequisiteStatus>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct PrerequisiteStatus {
+    // the id of the remote proposal on this chain
+    pub remote_prop_id: u64,
+    pub prereq: PrerequisiteProposal,
+    // the result of checking the prerequisite, if the proposal was created with validate_prereqs
+    pub validation: Option<PrerequisiteValidation>,
+    // whether the result of the prerequisite arrived
+    pub resolved: bool,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct SourceTally {
+    // the chain of a prerequisite remote proposal, not set for the votes cast on the proposal
+    pub chain: Option<String>,
+    // the id of the proposal on its chain
+    pub prop_id: u64,
     // option, num_votes
     pub tally: Vec<(String, u64)>,
+    pub total_power: u64,
+    // the outcome of a remote proposal, once its result arrived
+    pub outcome: Option<String>,
 }
 
 #[cosmwasm_schema::cw_serde]
 pub struct RemoteProposalMsg {
+    // the local proposal that has the remote proposal as a prerequisite
+    pub parent_prop_id: u64,
     pub prop_id: u64,
     pub remote_chain_id: String,
     pub remote_contract_addr: String,
 }
+
+#[cosmwasm_schema::cw_serde]
+pub struct RemoteVoteMsg {
+    pub prop_id: u64,
+    // the chain the vote was relayed from
+    pub chain: String,
+    pub voter: String,
+    pub options: Vec<(String, Decimal)>,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct RemoteTotalPowerMsg {
+    pub prop_id: u64,
+    pub chain: String,
+    // the start time of the proposal that the total power was queried at
+    pub time: Timestamp,
+}
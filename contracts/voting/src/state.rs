@@ -1,9 +1,60 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Timestamp;
+use cosmwasm_std::{Coin, CosmosMsg, Decimal, Empty, Timestamp, Uint128};
 use cw_storage_plus::Item;
 use cw_storage_plus::Map;
+use cw_utils::Expiration;
 
-pub const VOTING_PERIOD_IN_MINUTES: Item<u64> = Item::new("voting_period");
+use crate::error::ContractError;
+
+#[cw_serde]
+pub struct Config {
+    // The voting period of proposals that do not specify one, in minutes.
+    pub voting_period_in_minutes: u64,
+    // The bounds for the voting period of proposals, in minutes.
+    pub min_voting_period_in_minutes: u64,
+    pub max_voting_period_in_minutes: u64,
+    // The power contract used by proposals that do not specify one.
+    pub default_power_contract: Option<String>,
+    // The fraction of the total power that needs to vote for a proposal to be valid.
+    pub quorum: Decimal,
+    // The fraction of the votes cast that the winning option needs to exceed.
+    pub threshold: Decimal,
+    // The fraction of the votes cast for a NoWithVeto option above which a proposal is vetoed.
+    pub veto_threshold: Decimal,
+    // Whether voters can change their vote while the proposal is open.
+    pub allow_revoting: bool,
+    // Whether a proposal is executed as soon as the last result of its prerequisite remote
+    // proposals arrives, if its voting period has ended. Otherwise it needs another ExecuteProposal.
+    pub auto_execute: bool,
+    // The deposit a proposal needs before voting starts. If empty, proposals need no deposit.
+    pub min_deposit: Vec<Coin>,
+    // How long others can top up the deposit of a proposal, in minutes.
+    pub deposit_period_in_minutes: u64,
+    // Receives the deposits of vetoed proposals and proposals that failed the quorum.
+    // If not set, these deposits are burned.
+    pub deposit_recipient: Option<String>,
+    // The admin of the contract. If not set, the config can only be changed by governance.
+    pub admin: Option<String>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Power contracts that proposals are allowed to use, managed by the admin or governance.
+// Power contract address -> Empty
+pub const POWER_CONTRACT_WHITELIST: Map<String, Empty> = Map::new("power_contract_whitelist");
+
+// Modules on other chains whose module IBC messages are accepted, managed by the admin or governance.
+// Chain, module id (e.g. "ccgov-ns:ccgov") -> Empty
+pub const TRUSTED_MODULES: Map<(String, String), Empty> = Map::new("trusted_modules");
+
+// Ccgov instances on other chains that prerequisites can refer to, managed by the admin or governance.
+// Chain, ccgov contract address -> Empty
+pub const TRUSTED_INSTANCES: Map<(String, String), Empty> = Map::new("trusted_instances");
+
+// Power contracts on other chains that remote voting chains are allowed to use, managed by the admin or governance.
+// Chain, power contract address -> Empty
+pub const REMOTE_POWER_CONTRACT_WHITELIST: Map<(String, String), Empty> =
+    Map::new("remote_power_contract_whitelist");
 
 pub const PROP_ID: Item<u64> = Item::new("prop_id");
 
@@ -12,17 +63,206 @@ pub struct Proposal {
     pub id: u64,
     pub title: String,
     pub description: String,
+    // The address that created the proposal.
+    pub proposer: String,
+    // The start of the voting period. Set when the minimum deposit is reached,
+    // unless the proposal was scheduled to start later.
     pub start_time: Timestamp,
-    pub executed: bool,
-    pub options: Vec<String>,
+    // The voting period in minutes, if the end of voting is counted from the start time.
+    // Not set if the proposal was created with an explicit expiration.
+    pub voting_period_in_minutes: Option<u64>,
+    // When voting ends.
+    pub expiration: Expiration,
+    // The end of the deposit period.
+    pub deposit_end_time: Timestamp,
+    // The total deposit of the proposal, see DEPOSITS for the deposits of each depositor.
+    pub deposit: Vec<Coin>,
+    pub status: ProposalStatus,
+    pub options: Vec<ProposalOption>,
     // A contract address that is called to get the power of a voter.
-    // Contracts need to be first whitelisted by governance.
+    // Contracts need to be first whitelisted by governance, see POWER_CONTRACT_WHITELIST.
     pub power_contract: String,
 
     // A list of prerequisite proposals.
     // These are stored as REMOTE_PROPOSAL_IDs on the local chain, see REMOTE_PROPOSALS
-    // to see how they are matched to parameters that uniquely identify the proposal on the remote chain.
+    // to see how they are matched to the PrerequisiteProposal that identifies the proposal on the remote chain.
     pub prereq_proposals: Vec<u64>,
+    // How the results of the prerequisite proposals are combined with the local votes.
+    pub aggregation_strategy: AggregationStrategy,
+    // Chains whose voters can vote directly on the proposal, see REMOTE_VOTES.
+    pub remote_voting_chains: Vec<RemoteVotingChain>,
+}
+
+impl Proposal {
+    // Returns the option with the given name, if the proposal has one.
+    pub fn option(&self, name: &str) -> Option<&ProposalOption> {
+        self.options.iter().find(|option| option.name == name)
+    }
+}
+
+#[cw_serde]
+pub enum ProposalStatus {
+    // The proposal waits for the minimum deposit before voting starts.
+    DepositPeriod,
+    // The proposal is open for voting.
+    Open,
+    // The voting period has ended and the proposal can be executed.
+    PendingExecution,
+    // The proposal waits for the results of its prerequisite remote proposals or the total power
+    // of its remote voting chains.
+    PendingRemote,
+    // An option won the proposal, but it has no messages to dispatch.
+    Passed,
+    // No option got more than the threshold of the votes cast.
+    Rejected,
+    // An option won the proposal and its messages were dispatched.
+    Executed,
+    // Less than the quorum of the total power voted on the proposal,
+    // or the minimum deposit was not reached in the deposit period.
+    Failed,
+    // The proposal was cancelled and will not be executed.
+    Cancelled,
+}
+
+#[cw_serde]
+pub struct ProposalOption {
+    pub name: String,
+    // Messages that are dispatched by the contract when this option wins the proposal.
+    pub msgs: Vec<CosmosMsg>,
+    pub kind: OptionKind,
+}
+
+impl From<&str> for ProposalOption {
+    fn from(name: &str) -> Self {
+        ProposalOption {
+            name: name.to_string(),
+            msgs: vec![],
+            kind: OptionKind::Standard,
+        }
+    }
+}
+
+// A proposal on another chain whose result counts toward a local proposal.
+#[cw_serde]
+pub struct PrerequisiteProposal {
+    // the proposal id on the remote chain
+    pub prop_id: u64,
+    // the remote chain, e.g. "neutron"
+    pub chain: String,
+    // the address of ccgov on the remote chain
+    pub contract_addr: String,
+    // How the remote votes are scaled before they are combined with the local votes.
+    pub normalization: Normalization,
+    // Whether the remote chain is asked to push the result once the proposal is executed,
+    // instead of it being queried when the local proposal is executed.
+    pub subscribe: bool,
+}
+
+impl PrerequisiteProposal {
+    // A prerequisite whose votes are counted as they are and whose result is queried.
+    pub fn new(prop_id: u64, chain: impl Into<String>, contract_addr: impl Into<String>) -> Self {
+        PrerequisiteProposal {
+            prop_id,
+            chain: chain.into(),
+            contract_addr: contract_addr.into(),
+            normalization: Normalization::None,
+            subscribe: false,
+        }
+    }
+}
+
+// A chain whose voters can vote directly on a local proposal. Their votes are relayed by ccgov
+// on that chain, and their power is queried from a power contract there.
+#[cw_serde]
+pub struct RemoteVotingChain {
+    // the remote chain, e.g. "osmosis"
+    pub chain: String,
+    // the address of the power contract on the remote chain
+    pub power_contract: String,
+    // How the remote votes are scaled before they are combined with the local votes.
+    pub normalization: Normalization,
+}
+
+// The result of checking a prerequisite proposal on its chain when the parent proposal is created.
+#[cw_serde]
+pub enum PrerequisiteValidation {
+    // The proposal exists and has the same options as the parent proposal.
+    Valid,
+    // The proposal could not be queried, e.g. because of a wrong id or contract address.
+    NotFound,
+    // The proposal exists, but its options differ from those of the parent proposal.
+    IncompatibleOptions,
+}
+
+// Remote power comes from a power contract on another chain, so it is usually not comparable to
+// the local power. A normalization scales the votes and the total power of a remote proposal.
+#[cw_serde]
+pub enum Normalization {
+    // The remote power is counted as it is.
+    None,
+    // The remote power is multiplied by a fixed factor.
+    Multiplier { factor: Decimal },
+    // The remote votes count as the same fraction of the local total power as they are of the
+    // remote total power, i.e. the remote chain weighs as much as the local one.
+    FractionOfTotalPower,
+    // The remote power is scaled down so that the remote total power is at most max_power.
+    Cap { max_power: u64 },
+}
+
+impl Normalization {
+    // Scales remote power, rounding down. The remote total power is scaled with the same rule.
+    pub fn apply(
+        &self,
+        power: u64,
+        remote_total_power: u64,
+        local_total_power: u64,
+    ) -> Result<u64, ContractError> {
+        let power = Uint128::from(power);
+        let scaled = match self {
+            Normalization::None => power,
+            Normalization::Multiplier { factor } => power
+                .checked_mul_floor(*factor)
+                .map_err(|_| ContractError::PowerOverflow {})?,
+            Normalization::FractionOfTotalPower if remote_total_power == 0 => Uint128::zero(),
+            Normalization::FractionOfTotalPower => {
+                power.multiply_ratio(local_total_power, remote_total_power)
+            }
+            Normalization::Cap { max_power } if remote_total_power > *max_power => {
+                power.multiply_ratio(*max_power, remote_total_power)
+            }
+            Normalization::Cap { .. } => power,
+        };
+        u64::try_from(scaled.u128()).map_err(|_| ContractError::PowerOverflow {})
+    }
+}
+
+// How the votes of prerequisite remote proposals are combined with the local votes.
+#[cw_serde]
+pub enum AggregationStrategy {
+    // The remote votes and total power are added to the local ones.
+    Sum,
+    // Like Sum, but the votes and total power of each remote chain are multiplied by its weight.
+    // Chains without a weight count fully.
+    WeightedSum { weights: Vec<(String, Decimal)> },
+    // Only the local votes count, but the proposal is vetoed if any remote proposal was vetoed.
+    RemoteVeto,
+    // The local votes and every remote proposal need to result in the same winning option.
+    AllMustPass,
+    // The local votes determine the winning option, the remote votes only count toward the quorum.
+    LocalOnlyWithRemoteQuorum,
+}
+
+// How votes for an option are counted, following the Cosmos SDK x/gov model.
+// A proposal can have at most one Abstain and one NoWithVeto option.
+#[cw_serde]
+pub enum OptionKind {
+    // The option can win the proposal.
+    Standard,
+    // Votes count toward the quorum, but not toward the threshold. The option cannot win.
+    Abstain,
+    // Votes count against the other options. If they exceed the veto threshold of all votes
+    // cast, the proposal is rejected outright. The option cannot win.
+    NoWithVeto,
 }
 
 // Proposal ID -> Proposal
@@ -36,23 +276,76 @@ pub struct Vote {
     pub prop_id: u64,
     pub voter: String,
     pub power: u64,
-    pub option: String,
+    // The options voted for and the fraction of the power given to each. The weights sum to 1.
+    pub options: Vec<(String, Decimal)>,
 }
 
 // Prop_Id, Voter -> Vote
 pub const VOTE_MAP: Map<(u64, String), Vote> = Map::new("vote_map");
 
-// bvector of (proposal id, option that won)
-pub const EXECUTED_PROPOSALS: Item<Vec<(u64, String)>> = Item::new("executed_proposals");
+// Voter, Prop_Id -> () secondary index on VOTE_MAP, kept in sync whenever a vote is saved
+pub const VOTES_BY_VOTER: Map<(String, u64), Empty> = Map::new("votes_by_voter");
+
+// Prop_Id, remote chain, voter on the remote chain -> Vote relayed from the remote chain.
+// The power of the vote is the power of the voter on the remote chain, before normalization.
+pub const REMOTE_VOTES: Map<(u64, String, String), Vote> = Map::new("remote_votes");
+
+// Prop_Id, remote chain -> total power of the power contract of a remote voting chain
+pub const REMOTE_VOTING_TOTAL_POWER: Map<(u64, String), u64> =
+    Map::new("remote_voting_total_power");
+
+// Prop_Id, Depositor -> Deposit
+pub const DEPOSITS: Map<(u64, String), Vec<Coin>> = Map::new("deposits");
+
+// Delegator -> Delegate
+pub const DELEGATIONS: Map<String, String> = Map::new("delegations");
+
+// Delegate, Delegator -> () reverse index on DELEGATIONS
+pub const DELEGATORS: Map<(String, String), Empty> = Map::new("delegators");
+
+// Prop_Id, Delegator -> Delegate whose vote on the proposal includes the power of the delegator.
+// The delegator's power is counted at most once per proposal, even if they re-delegate.
+pub const DELEGATED_VOTES: Map<(u64, String), String> = Map::new("delegated_votes");
+
+// Proposal ID -> option that won
+// If no option won, the outcome is one of FAILED_QUORUM_OUTCOME, THRESHOLD_NOT_REACHED_OUTCOME,
+// VETOED_OUTCOME or DEPOSIT_NOT_REACHED_OUTCOME.
+pub const EXECUTED_PROPOSALS: Map<u64, String> = Map::new("executed_proposals");
+
+// Outcome of proposals for which less than the quorum of the total power voted.
+pub const FAILED_QUORUM_OUTCOME: &str = "failed_quorum";
+// Outcome of proposals for which no option got more than the threshold of the votes cast.
+pub const THRESHOLD_NOT_REACHED_OUTCOME: &str = "threshold_not_reached";
+
+// Outcome of proposals that did not reach the minimum deposit in the deposit period.
+pub const DEPOSIT_NOT_REACHED_OUTCOME: &str = "deposit_not_reached";
+
+// Outcome of proposals for which the NoWithVeto votes exceeded the veto threshold.
+pub const VETOED_OUTCOME: &str = "vetoed";
+
+// Prop_Id, subscribing chain, remote proposal id on the subscribing chain -> parent proposal id
+// on the subscribing chain. Subscribers are sent the tally of the proposal once it is executed.
+pub const SUBSCRIBERS: Map<(u64, String, u64), u64> = Map::new("subscribers");
 
 // running REMOTE_PROPOSAL_ID
 pub const REMOTE_PROPOSAL_ID: Item<u64> = Item::new("remote_proposal_id");
 
-// proposal id of the remote proposal on this chain -> Remote proposal id, remote chain id, remote contract address
-pub const REMOTE_PROPOSALS: Map<u64, (u64, String, String)> = Map::new("remote_proposals");
+// proposal id of the remote proposal on this chain -> the prerequisite proposal on the remote chain
+pub const REMOTE_PROPOSALS: Map<u64, PrerequisiteProposal> = Map::new("remote_proposals");
 
 // Remote proposal id on this chain, option -> num_votes
 pub const REMOTE_PROPOSALS_TALLIES: Map<(u64, String), u64> = Map::new("remote_proposals_tallies");
 
+// Remote proposal id on this chain -> total power of the remote proposal
+pub const REMOTE_PROPOSALS_TOTAL_POWER: Map<u64, u64> = Map::new("remote_proposals_total_power");
+
+// Remote proposal id on this chain -> outcome of the remote proposal
+pub const REMOTE_PROPOSAL_OUTCOMES: Map<u64, String> = Map::new("remote_proposal_outcomes");
+
 // Remote proposal id on this chain -> resolved
 pub const REMOTE_PROPOSAL_RESOLVED: Map<u64, bool> = Map::new("remote_proposal_resolved");
+
+// Remote proposal id on this chain -> result of checking the prerequisite on its chain.
+// Only set for proposals that were created with validate_prereqs, once the check returned.
+pub const REMOTE_PROPOSAL_VALIDATIONS: Map<u64, PrerequisiteValidation> =
+    Map::new("remote_proposal_validations");
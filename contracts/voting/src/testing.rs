@@ -1,29 +1,35 @@
-use std::io::empty;
-use std::ops::{Add, Deref, DerefMut};
-
-use abstract_interface::ManagerExecFns;
-use cosmwasm_std::testing::{mock_env, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{coin, coins, Addr, BlockInfo, Event};
-use cw_orch::mock::cw_multi_test::{App, BankKeeper, Contract, ContractWrapper, Executor};
-use cw_orch::mock::MockBase;
-use cw_orch_interchain::{MockBech32InterchainEnv, MockInterchainEnv};
-use std::iter::Iterator;
-
-use crate::contract::{execute_handler, instantiate_handler, query_handler};
+use abstract_app::std::ibc::ModuleIbcMsg;
+use abstract_app::std::manager::ExecuteMsg as ManagerExecuteMsg;
+use abstract_app::std::objects::{
+    account::AccountTrace, chain_name::ChainName, module::ModuleInfo, AccountId,
+};
+use abstract_interface::{Abstract, InstallConfig, Manager, ManagerExecFns, VCQueryFns};
+use cosmwasm_std::{
+    coins, to_json_binary, wasm_execute, Addr, BankMsg, Coin, Decimal, Event, Timestamp,
+};
+use cw_orch::mock::cw_multi_test::{AppResponse, ContractWrapper, Executor};
+use cw_orch_interchain::{InterchainError, MockBech32InterchainEnv};
+use cw_utils::Expiration;
 
+use crate::contract::{MAX_DELEGATORS, MAX_MULTIPLIER, MAX_SUBSCRIBERS};
+use crate::error::ContractError;
 use crate::msg::{
-    CCGovExecuteMsgFns, CCGovInstantiateMsg, CCGovQueryMsgFns, GetVotingPowerResponse,
-    InstantiateMsg,
+    CCGovExecuteMsg, CCGovExecuteMsgFns, CCGovIbcMessage, CCGovInstantiateMsg, CCGovQueryMsgFns,
+    ExecuteMsg, GetVotingPowerResponse, PowerContractQueryMsg, QueryTallyResponse,
+};
+use crate::state::{
+    AggregationStrategy, Normalization, OptionKind, PrerequisiteProposal, PrerequisiteValidation,
+    ProposalOption, ProposalStatus, RemoteVotingChain, DEPOSIT_NOT_REACHED_OUTCOME,
+    FAILED_QUORUM_OUTCOME, THRESHOLD_NOT_REACHED_OUTCOME, VETOED_OUTCOME,
+};
+use crate::{APP_VERSION, CCGOV_ID, CCGOV_NAMESPACE};
+use fixed_power::msg::{
+    InstantiateMsg as FixedPowerInstantiateMsg, QueryMsg as FixedPowerQueryMsg,
 };
-use crate::CCGOV_NAMESPACE;
-use fixed_power::msg::{GetVotingPowerMsg, InstantiateMsg as FixedPowerInstantiateMsg};
 
 use abstract_client::{AbstractClient, Application, Environment};
 use abstract_interchain_tests::setup::ibc_connect_polytone_and_abstract;
 use cw_orch_interchain::prelude::*;
-use cw_orch_interchain::InterchainEnv;
-
-use cw_orch::prelude::*;
 
 use cw_orch::{anyhow, prelude::*};
 
@@ -42,9 +48,34 @@ pub const A_SENDER: &str = "kujira18k2uq7srsr8lwrae6zr0qahpn29rsp7tfassws";
 pub const B_CHAIN_ID: &str = "neutron-1";
 pub const B_SENDER: &str = "neutron18k2uq7srsr8lwrae6zr0qahpn29rsp7tu2m2ea";
 
+/// Instantiate message with a one minute voting period and no quorum or threshold
+fn instantiate_msg(admin: &Addr) -> CCGovInstantiateMsg {
+    CCGovInstantiateMsg {
+        voting_period_in_minutes: 1,
+        min_voting_period_in_minutes: 1,
+        max_voting_period_in_minutes: 60,
+        default_power_contract: None,
+        quorum: Decimal::zero(),
+        threshold: Decimal::zero(),
+        veto_threshold: Decimal::one(),
+        allow_revoting: false,
+        auto_execute: false,
+        min_deposit: vec![],
+        deposit_period_in_minutes: 0,
+        deposit_recipient: None,
+        admin: Some(admin.to_string()),
+    }
+}
+
 impl<Env: CwEnv> TestEnv<Env> {
     /// Set up the test environment with an Account that has the App installed
     fn setup(env: Env) -> anyhow::Result<TestEnv<Env>> {
+        let msg = instantiate_msg(&env.sender());
+        Self::setup_with_msg(env, msg)
+    }
+
+    /// Set up the test environment with an Account that has the App installed with the given config
+    fn setup_with_msg(env: Env, msg: CCGovInstantiateMsg) -> anyhow::Result<TestEnv<Env>> {
         // Create a sender and mock env
         let namespace = Namespace::new(CCGOV_NAMESPACE)?;
 
@@ -57,7 +88,7 @@ impl<Env: CwEnv> TestEnv<Env> {
 
         let app = publisher
             .account()
-            .install_app::<CCGovInterface<_>>(&CCGovInstantiateMsg {}, &[])?;
+            .install_app::<CCGovInterface<_>>(&msg, &[])?;
 
         app.account().as_ref().manager.update_settings(Some(true))?; // enable ibc
 
@@ -73,9 +104,176 @@ impl<Env: CwEnv> TestEnv<Env> {
     }
 }
 
+/// Store and instantiate a fixed power contract with the given total power on the given chain
+fn instantiate_fixed_power(chain: &MockBech32, total_power: u64) -> Addr {
+    let mut app = chain.app.borrow_mut();
+
+    let fixed_power_code = ContractWrapper::new(
+        fixed_power::contract::execute,
+        fixed_power::contract::instantiate,
+        fixed_power::contract::query,
+    );
+    let fixed_power_code_id = app.store_code(Box::new(fixed_power_code));
+    app.instantiate_contract(
+        fixed_power_code_id,
+        Addr::unchecked("owner"),
+        &FixedPowerInstantiateMsg { total_power },
+        &[],
+        "FixedPower",
+        None,
+    )
+    .unwrap()
+}
+
+/// Power contract that reports the number of seconds of the queried time as power,
+/// so tests can check which point in time power is queried at
+fn snapshot_power_query(
+    _deps: cosmwasm_std::Deps,
+    env: cosmwasm_std::Env,
+    msg: PowerContractQueryMsg,
+) -> cosmwasm_std::StdResult<cosmwasm_std::Binary> {
+    let time = match msg {
+        PowerContractQueryMsg::GetVotingPowerMsg(msg) => msg.time,
+        PowerContractQueryMsg::GetTotalPowerMsg(msg) => msg.time,
+    };
+    let power = time.unwrap_or(env.block.time).seconds();
+    cosmwasm_std::to_json_binary(&GetVotingPowerResponse { power })
+}
+
+fn instantiate_snapshot_power(chain: &MockBech32) -> Addr {
+    let mut app = chain.app.borrow_mut();
+
+    let snapshot_power_code = ContractWrapper::new(
+        fixed_power::contract::execute,
+        fixed_power::contract::instantiate,
+        snapshot_power_query,
+    );
+    let snapshot_power_code_id = app.store_code(Box::new(snapshot_power_code));
+    app.instantiate_contract(
+        snapshot_power_code_id,
+        Addr::unchecked("owner"),
+        &FixedPowerInstantiateMsg { total_power: 0 },
+        &[],
+        "SnapshotPower",
+        None,
+    )
+    .unwrap()
+}
+
+/// CreateProposal arguments for tests, with defaults for everything but the power contract
+#[derive(Clone)]
+struct TestProposal {
+    title: String,
+    description: String,
+    options: Vec<ProposalOption>,
+    prereq_proposals: Vec<PrerequisiteProposal>,
+    power_contract_addr: Option<String>,
+    voting_period_in_minutes: Option<u64>,
+    start_time: Option<Timestamp>,
+    expiration: Option<Expiration>,
+    aggregation_strategy: Option<AggregationStrategy>,
+    remote_voting_chains: Option<Vec<RemoteVotingChain>>,
+    validate_prereqs: Option<bool>,
+    deposit: Vec<Coin>,
+}
+
+impl TestProposal {
+    /// An approve/reject proposal without prerequisites, using the given power contract
+    fn new(power_contract: &Addr) -> Self {
+        Self {
+            title: "test".to_string(),
+            description: "cosmwasm is awesome".to_string(),
+            options: vec!["approve".into(), "reject".into()],
+            prereq_proposals: vec![],
+            power_contract_addr: Some(power_contract.to_string()),
+            voting_period_in_minutes: None,
+            start_time: None,
+            expiration: None,
+            aggregation_strategy: None,
+            remote_voting_chains: None,
+            validate_prereqs: None,
+            deposit: vec![],
+        }
+    }
+
+    /// Create the proposal as the sender of the given app
+    fn create(self, app: &CCGovInterface<MockBech32>) -> Result<AppResponse, CwOrchError> {
+        app.create_proposal(
+            self.description,
+            self.options,
+            self.prereq_proposals,
+            self.title,
+            self.aggregation_strategy,
+            self.expiration,
+            self.power_contract_addr,
+            self.remote_voting_chains,
+            self.start_time,
+            self.validate_prereqs,
+            self.voting_period_in_minutes,
+            &self.deposit,
+        )
+    }
+}
+
+/// Assert that the result failed with the given contract error
+#[track_caller]
+fn assert_contract_err<T: std::fmt::Debug>(
+    result: Result<T, impl Into<anyhow::Error>>,
+    expected: ContractError,
+) {
+    let err: anyhow::Error = match result {
+        Ok(response) => panic!("expected error \"{expected}\", got {response:?}"),
+        Err(err) => err.into(),
+    };
+    assert_eq!(err.root_cause().to_string(), expected.to_string());
+}
+
+/// Assert that a packet of the relayed transaction was acknowledged with the given contract error
+#[track_caller]
+fn assert_ibc_err<T: std::fmt::Debug>(result: Result<T, InterchainError>, expected: ContractError) {
+    match result {
+        Err(InterchainError::AckDecodingFailed(ack, _)) => {
+            let ack = String::from_utf8_lossy(ack.as_slice()).to_string();
+            assert!(ack.contains(&expected.to_string()), "{ack}");
+        }
+        other => panic!("expected error ack \"{expected}\", got {other:?}"),
+    }
+}
+
+/// Deliver a message from ccgov on the given chain, as the IBC host would
+fn deliver_module_ibc(
+    app: &CCGovInterface<MockBech32>,
+    client_chain_id: &str,
+    msg: &impl serde::Serialize,
+) -> anyhow::Result<()> {
+    let ccgov = ModuleInfo::from_id(CCGOV_ID, APP_VERSION.into())?;
+    deliver_module_ibc_from(app, client_chain_id, ccgov, msg)
+}
+
+/// Deliver a message from the given module on the given chain, as the IBC host would
+fn deliver_module_ibc_from(
+    app: &CCGovInterface<MockBech32>,
+    client_chain_id: &str,
+    source_module: ModuleInfo,
+    msg: &impl serde::Serialize,
+) -> anyhow::Result<()> {
+    let ibc_host = Abstract::load_from(app.get_chain().clone())?
+        .ibc
+        .host
+        .address()?;
+    app.call_as(&ibc_host).execute(
+        &ExecuteMsg::ModuleIbc(ModuleIbcMsg {
+            client_chain: ChainName::from_chain_id(client_chain_id),
+            source_module,
+            msg: to_json_binary(msg)?,
+        }),
+        None,
+    )?;
+    Ok(())
+}
+
 #[test]
 fn multi_chain_test() -> anyhow::Result<()> {
-    let sender = Addr::unchecked("sender_for_all_chains");
     let interchain =
         MockBech32InterchainEnv::new(vec![(A_CHAIN_ID, A_SENDER), (B_CHAIN_ID, B_SENDER)]);
 
@@ -94,74 +292,47 @@ fn multi_chain_test() -> anyhow::Result<()> {
     let a_app = a_env.app;
     let b_app = b_env.app;
 
-    let mut a_fixed_power_addr: Addr = Addr::unchecked("fixed-power");
-    {
-        let mut app = a_app.get_chain().app.borrow_mut();
-
-        let fixed_power_code = ContractWrapper::new(
-            fixed_power::contract::execute,
-            fixed_power::contract::instantiate,
-            fixed_power::contract::query,
-        );
-        let fixed_power_code_id = app.store_code(Box::new(fixed_power_code));
-        a_fixed_power_addr = app
-            .instantiate_contract(
-                fixed_power_code_id,
-                Addr::unchecked("owner"),
-                &FixedPowerInstantiateMsg {},
-                &[],
-                "FixedPower",
-                None,
-            )
-            .unwrap();
-    }
-
-    let mut b_fixed_power_addr: Addr = Addr::unchecked("fixed-power");
-    {
-        let mut app = b_app.get_chain().app.borrow_mut();
-
-        let fixed_power_code = ContractWrapper::new(
-            fixed_power::contract::execute,
-            fixed_power::contract::instantiate,
-            fixed_power::contract::query,
-        );
-        let fixed_power_code_id = app.store_code(Box::new(fixed_power_code));
-        b_fixed_power_addr = app
-            .instantiate_contract(
-                fixed_power_code_id,
-                Addr::unchecked("owner"),
-                &FixedPowerInstantiateMsg {},
-                &[],
-                "FixedPower",
-                None,
-            )
-            .unwrap();
-    }
+    let a_fixed_power_addr = instantiate_fixed_power(a_app.get_chain(), 10);
+    let b_fixed_power_addr = instantiate_fixed_power(b_app.get_chain(), 10);
+    a_app.add_power_contract(a_fixed_power_addr.to_string())?;
+    b_app.add_power_contract(b_fixed_power_addr.to_string())?;
 
     // create proposal on chain A
-    let create_prop_response = a_app.create_proposal(
-        "cosmwasm is awesome".to_string(),
-        vec!["approve".to_string(), "reject".to_string()],
-        a_fixed_power_addr.to_string(),
-        vec![], // no prerequisites
-        "test".to_string(),
-    );
+    let create_prop_response = TestProposal::new(&a_fixed_power_addr).create(&a_app);
 
     // ensure the proposal was created ok
     assert!(create_prop_response.is_ok(), "{:?}", create_prop_response);
 
     // create proposal on chain b which references chain As proposal
-    let create_prop_response = b_app.create_proposal(
-        "cosmwasm is awesome".to_string(),
-        vec!["approve".to_string(), "reject".to_string()],
-        b_fixed_power_addr.to_string(),
-        vec![(
+    let prereq_proposal = TestProposal {
+        prereq_proposals: vec![PrerequisiteProposal::new(
             0,
             "harpoon".to_string(),
             a_app.as_instance().address()?.to_string(),
         )],
-        "test".to_string(),
+        ..TestProposal::new(&b_fixed_power_addr)
+    };
+
+    // prerequisites can only refer to trusted ccgov instances
+    assert_contract_err(
+        prereq_proposal.clone().create(&b_app),
+        ContractError::UntrustedPrerequisite {},
+    );
+    b_app.add_trusted_instance(
+        "harpoon".to_string(),
+        a_app.as_instance().address()?.to_string(),
+    )?;
+    assert_eq!(
+        b_app.list_trusted_instances(None, None)?.trusted_instances,
+        vec![(
+            "harpoon".to_string(),
+            a_app.as_instance().address()?.to_string()
+        )]
     );
+    let create_prop_response = prereq_proposal.create(&b_app);
+
+    // ensure the proposal was created ok
+    assert!(create_prop_response.is_ok(), "{:?}", create_prop_response);
 
     // vote on chain A
     let vote_response = a_app.vote("approve".to_string(), 0);
@@ -170,7 +341,7 @@ fn multi_chain_test() -> anyhow::Result<()> {
     assert!(vote_response.is_ok(), "{:?}", vote_response);
 
     // make time pass on chain A so the proposal can be executed
-    a_app.get_chain().wait_seconds(60);
+    a_app.get_chain().wait_seconds(60)?;
 
     // vote on chain B
     let vote_response = b_app.vote("reject".to_string(), 0);
@@ -179,7 +350,7 @@ fn multi_chain_test() -> anyhow::Result<()> {
     assert!(vote_response.is_ok(), "{:?}", vote_response);
 
     // make time pass on chain B so the proposal can be executed
-    b_app.get_chain().wait_seconds(60);
+    b_app.get_chain().wait_seconds(60)?;
 
     // try to execute proposal on chain B
     let execute_proposal_response = b_app.execute_proposal(0)?;
@@ -190,6 +361,10 @@ fn multi_chain_test() -> anyhow::Result<()> {
         "{:?}",
         execute_proposal_response
     );
+    assert_eq!(
+        b_app.query_proposal(0)?.prop.status,
+        ProposalStatus::PendingRemote
+    );
 
     interchain.check_ibc(B_CHAIN_ID, execute_proposal_response)?;
 
@@ -208,6 +383,12 @@ fn multi_chain_test() -> anyhow::Result<()> {
 
     interchain.check_ibc(B_CHAIN_ID, execute_proposal_response)?;
 
+    // the result of the proposal on chain A arrived, so the proposal can be executed
+    assert_eq!(
+        b_app.query_proposal(0)?.prop.status,
+        ProposalStatus::PendingExecution
+    );
+
     // now try to execute the proposal on chain
     let execute_proposal_response = b_app.execute_proposal(0)?;
 
@@ -233,134 +414,2184 @@ fn multi_chain_test() -> anyhow::Result<()> {
 }
 
 #[test]
-fn fixed_power_test() -> anyhow::Result<()> {
-    let mock = MockBech32::new("mock");
+fn push_remote_results_test() -> anyhow::Result<()> {
+    let interchain =
+        MockBech32InterchainEnv::new(vec![(A_CHAIN_ID, A_SENDER), (B_CHAIN_ID, B_SENDER)]);
 
-    let env = TestEnv::setup(mock)?;
+    let a = interchain.chain(A_CHAIN_ID)?;
+    let b = interchain.chain(B_CHAIN_ID)?;
 
-    env_logger::try_init();
-    let app = env.app;
+    let a_env = TestEnv::setup(a.clone())?;
+    let b_env = TestEnv::setup(b.clone())?;
 
-    let sender: Addr = app.get_chain().sender.clone();
+    a_env.enable_ibc()?;
+    b_env.enable_ibc()?;
 
-    let fixed_power_code = ContractWrapper::new(
-        fixed_power::contract::execute,
-        fixed_power::contract::instantiate,
-        fixed_power::contract::query,
+    ibc_connect_polytone_and_abstract(&interchain, B_CHAIN_ID, A_CHAIN_ID)?;
+    ibc_connect_polytone_and_abstract(&interchain, A_CHAIN_ID, B_CHAIN_ID)?;
+
+    let a_app = a_env.app;
+    let b_name = ChainName::from_chain_id(B_CHAIN_ID).to_string();
+
+    // module IBC messages of chain A reach the ccgov on the remote account of its account on chain B
+    let a_account = a_app.account().as_ref();
+    let register_response = a_account.manager.register_remote_account(&b_name)?;
+    interchain.check_ibc(A_CHAIN_ID, register_response)?;
+    let install_response = a_account.manager.execute_on_remote(
+        &b_name,
+        ManagerExecuteMsg::InstallModules {
+            modules: vec![CCGovInterface::<MockBech32>::install_config(
+                &instantiate_msg(&b.sender()),
+            )?],
+        },
+    )?;
+    interchain.check_ibc(A_CHAIN_ID, install_response)?;
+
+    let remote_account_id = AccountId::new(
+        a_app.account().id()?.seq(),
+        AccountTrace::Remote(vec![ChainName::from_chain_id(A_CHAIN_ID)]),
+    )?;
+    let remote_manager = Manager::new("remote_manager", b.clone());
+    remote_manager.set_address(
+        &b_env
+            .abs
+            .version_control()
+            .account_base(remote_account_id)?
+            .account_base
+            .manager,
     );
-    let mut fixed_power_addr: Addr = Addr::unchecked("fixed-power");
-    {
-        let mut app2 = app.get_chain().app.borrow_mut();
-
-        let fixed_power_code_id = app2.store_code(Box::new(fixed_power_code));
-        fixed_power_addr = app2
-            .instantiate_contract(
-                fixed_power_code_id,
-                Addr::unchecked("owner"),
-                &FixedPowerInstantiateMsg {},
-                &[],
-                "FixedPower",
-                None,
+    let b_app = CCGovInterface::new(CCGOV_ID, b.clone());
+    b_app.set_address(&remote_manager.module_info(CCGOV_ID)?.unwrap().address);
+
+    let a_fixed_power_addr = instantiate_fixed_power(&a, 10);
+    let b_fixed_power_addr = instantiate_fixed_power(&b, 10);
+    a_app.add_power_contract(a_fixed_power_addr.to_string())?;
+    b_app.add_power_contract(b_fixed_power_addr.to_string())?;
+    a_app.add_trusted_module(b_name.clone(), CCGOV_ID.to_string())?;
+    b_app.add_trusted_module(
+        ChainName::from_chain_id(A_CHAIN_ID).to_string(),
+        CCGOV_ID.to_string(),
+    )?;
+
+    let a_app_addr = a_app.as_instance().address()?.to_string();
+    b_app.add_trusted_instance(
+        ChainName::from_chain_id(A_CHAIN_ID).to_string(),
+        a_app_addr.clone(),
+    )?;
+    TestProposal::new(&a_fixed_power_addr).create(&a_app)?;
+    TestProposal {
+        prereq_proposals: vec![PrerequisiteProposal {
+            subscribe: true,
+            ..PrerequisiteProposal::new(
+                0,
+                ChainName::from_chain_id(A_CHAIN_ID).to_string(),
+                a_app_addr.clone(),
             )
-            .unwrap();
+        }],
+        ..TestProposal::new(&b_fixed_power_addr)
+    }
+    .create(&b_app)?;
+
+    // subscriptions need to refer to the receiving ccgov
+    let subscribe = |prop_id, remote_prop_id, contract_addr: &str| {
+        deliver_module_ibc(
+            &a_app,
+            B_CHAIN_ID,
+            &CCGovIbcMessage::Subscribe {
+                prop_id,
+                parent_prop_id: 0,
+                remote_prop_id,
+                contract_addr: contract_addr.to_string(),
+            },
+        )
+    };
+    assert_contract_err(
+        subscribe(0, 0, b_fixed_power_addr.as_str()),
+        ContractError::UnauthorizedIbcMessage {},
+    );
 
-        let fixed_power_querymsg = GetVotingPowerMsg {
-            voter: "voter".to_string(),
-        };
+    // the subscription of chain B arrives on chain A through the IBC host
+    subscribe(0, 0, &a_app_addr)?;
 
-        let fixed_power_response: Result<GetVotingPowerResponse, _> = app2
-            .wrap()
-            .query_wasm_smart(fixed_power_addr.clone(), &fixed_power_querymsg);
+    // a proposal only accepts a bounded number of subscribers
+    TestProposal::new(&a_fixed_power_addr).create(&a_app)?;
+    for remote_prop_id in 0..MAX_SUBSCRIBERS as u64 {
+        subscribe(1, remote_prop_id, &a_app_addr)?;
+    }
+    subscribe(1, 0, &a_app_addr)?;
+    assert_contract_err(
+        subscribe(1, MAX_SUBSCRIBERS as u64, &a_app_addr),
+        ContractError::TooManySubscribers {
+            max: MAX_SUBSCRIBERS,
+        },
+    );
 
-        // print the response
-        println!("{:?}", fixed_power_response);
+    a_app.vote("approve".to_string(), 0)?;
+    b_app.vote("approve".to_string(), 0)?;
+    a.wait_seconds(60)?;
+    b.wait_seconds(60)?;
 
-        assert!(fixed_power_response.is_ok());
-        assert_eq!(
-            fixed_power_response.unwrap(),
-            GetVotingPowerResponse { power: 1 }
-        );
-    }
-    let create_prop_response = app.create_proposal(
-        "cosmwasm is awesome".to_string(),
-        vec!["approve".to_string(), "reject".to_string()],
-        fixed_power_addr.to_string(),
-        vec![], // no prerequisites
-        "test".to_string(),
+    // executing the proposal on chain A sends its tally to chain B
+    let execute_response = a_app.execute_proposal(0)?;
+    interchain.check_ibc(A_CHAIN_ID, execute_response)?;
+
+    // the proposal on chain B is executed without querying chain A
+    let execute_response = b_app.execute_proposal(0)?;
+    assert!(!execute_response.has_event(&Event::new("wasm-remote_proposal_unresolved")));
+    assert_eq!(b_app.query_proposal(0)?.prop.status, ProposalStatus::Passed);
+    assert_eq!(
+        b_app.query_tally(0)?.tally,
+        vec![("approve".to_string(), 2), ("reject".to_string(), 0)]
     );
 
-    // ensure the proposal was created ok
-    assert!(create_prop_response.is_ok(), "{:?}", create_prop_response);
+    // results can only be pushed by ccgov on the chain of the remote proposal
+    let push_result = |client_chain_id, contract_addr: &str| -> anyhow::Result<()> {
+        deliver_module_ibc(
+            &b_app,
+            client_chain_id,
+            &CCGovIbcMessage::ProposalResult {
+                prop_id: 0,
+                parent_prop_id: 0,
+                remote_prop_id: 0,
+                tally: a_app.query_tally(0)?,
+                contract_addr: contract_addr.to_string(),
+            },
+        )
+    };
+    assert_contract_err(
+        push_result(B_CHAIN_ID, &a_app_addr),
+        ContractError::UnauthorizedIbcMessage {},
+    );
+    assert_contract_err(
+        push_result(A_CHAIN_ID, a_fixed_power_addr.as_str()),
+        ContractError::UnauthorizedIbcMessage {},
+    );
 
-    // query the proposal
-    let query_response = app.query_proposal(0)?;
+    Ok(())
+}
 
-    // ensure the proposal was created correctly
-    assert_eq!(query_response.prop.id, 0);
-    assert_eq!(query_response.prop.title, "test");
-    assert_eq!(query_response.prop.description, "cosmwasm is awesome");
+#[test]
+fn remote_vote_test() -> anyhow::Result<()> {
+    let interchain =
+        MockBech32InterchainEnv::new(vec![(A_CHAIN_ID, A_SENDER), (B_CHAIN_ID, B_SENDER)]);
+
+    let a = interchain.chain(A_CHAIN_ID)?;
+    let b = interchain.chain(B_CHAIN_ID)?;
+
+    let a_env = TestEnv::setup(a.clone())?;
+    let b_env = TestEnv::setup(b.clone())?;
+
+    a_env.enable_ibc()?;
+    b_env.enable_ibc()?;
+
+    ibc_connect_polytone_and_abstract(&interchain, B_CHAIN_ID, A_CHAIN_ID)?;
+    ibc_connect_polytone_and_abstract(&interchain, A_CHAIN_ID, B_CHAIN_ID)?;
+
+    let a_app = a_env.app;
+    let a_name = ChainName::from_chain_id(A_CHAIN_ID).to_string();
+    let b_name = ChainName::from_chain_id(B_CHAIN_ID).to_string();
+
+    // votes from chain A reach the ccgov on the remote account of its account on chain B
+    let a_account = a_app.account().as_ref();
+    let register_response = a_account.manager.register_remote_account(&b_name)?;
+    interchain.check_ibc(A_CHAIN_ID, register_response)?;
+    let install_response = a_account.manager.execute_on_remote(
+        &b_name,
+        ManagerExecuteMsg::InstallModules {
+            modules: vec![CCGovInterface::<MockBech32>::install_config(
+                &instantiate_msg(&b.sender()),
+            )?],
+        },
+    )?;
+    interchain.check_ibc(A_CHAIN_ID, install_response)?;
+    // the remote account needs IBC for the power queries of chain B
+    let settings_response = a_account.manager.execute_on_remote(
+        &b_name,
+        ManagerExecuteMsg::UpdateSettings {
+            ibc_enabled: Some(true),
+        },
+    )?;
+    interchain.check_ibc(A_CHAIN_ID, settings_response)?;
+
+    let remote_account_id = AccountId::new(
+        a_app.account().id()?.seq(),
+        AccountTrace::Remote(vec![ChainName::from_chain_id(A_CHAIN_ID)]),
+    )?;
+    let remote_manager = Manager::new("remote_manager", b.clone());
+    remote_manager.set_address(
+        &b_env
+            .abs
+            .version_control()
+            .account_base(remote_account_id)?
+            .account_base
+            .manager,
+    );
+    let b_app = CCGovInterface::new(CCGOV_ID, b.clone());
+    b_app.set_address(&remote_manager.module_info(CCGOV_ID)?.unwrap().address);
+
+    let a_fixed_power_addr = instantiate_fixed_power(&a, 100);
+    let b_fixed_power_addr = instantiate_fixed_power(&b, 10);
+    b_app.add_power_contract(b_fixed_power_addr.to_string())?;
+    b_app.add_trusted_module(a_name.clone(), CCGOV_ID.to_string())?;
+
+    // voters on chain A can vote on the proposal on chain B with their power on chain A,
+    // whose total power is queried when the proposal is created
+    let remote_voting_proposal = TestProposal {
+        remote_voting_chains: Some(vec![RemoteVotingChain {
+            chain: a_name.clone(),
+            power_contract: a_fixed_power_addr.to_string(),
+            normalization: Normalization::None,
+        }]),
+        ..TestProposal::new(&b_fixed_power_addr)
+    };
+
+    // the power contracts of remote voting chains must be whitelisted for their chain
+    assert_contract_err(
+        remote_voting_proposal.clone().create(&b_app),
+        ContractError::PowerContractNotWhitelisted {},
+    );
+    b_app.add_remote_power_contract(b_name.clone(), a_fixed_power_addr.to_string())?;
+    assert_contract_err(
+        remote_voting_proposal.clone().create(&b_app),
+        ContractError::PowerContractNotWhitelisted {},
+    );
+    b_app.remove_remote_power_contract(b_name.clone(), a_fixed_power_addr.to_string())?;
+    b_app.add_remote_power_contract(a_name.clone(), a_fixed_power_addr.to_string())?;
     assert_eq!(
-        query_response.prop.options,
-        vec!["approve".to_string(), "reject".to_string()]
+        b_app
+            .list_remote_power_contracts(None, None)?
+            .remote_power_contracts,
+        vec![(a_name.clone(), a_fixed_power_addr.to_string())]
     );
-    // print the power contract address
+
+    let create_response = remote_voting_proposal.clone().create(&b_app)?;
+    interchain.check_ibc(B_CHAIN_ID, create_response)?;
+    assert_eq!(b_app.query_tally(0)?.total_power, 110);
+    b_app.vote("approve".to_string(), 0)?;
+
+    for voter in ["voter0", "voter1"] {
+        let voter = a.addr_make(voter);
+        let vote_response = a_app.call_as(&voter).remote_vote(
+            b_name.clone(),
+            vec![("reject".to_string(), Decimal::one())],
+            0,
+        )?;
+        interchain.check_ibc(A_CHAIN_ID, vote_response)?;
+    }
+
+    let tally = b_app.query_tally(0)?;
     assert_eq!(
-        query_response.prop.power_contract,
-        fixed_power_addr.to_string()
+        tally.tally,
+        vec![("approve".to_string(), 1), ("reject".to_string(), 2)]
+    );
+    assert_eq!(tally.total_power, 110);
+
+    // a remote voter cannot vote twice without revoting
+    let vote_response = a_app.call_as(&a.addr_make("voter0")).remote_vote(
+        b_name.clone(),
+        vec![("approve".to_string(), Decimal::one())],
+        0,
+    )?;
+    assert_ibc_err(
+        interchain.check_ibc(A_CHAIN_ID, vote_response),
+        ContractError::AlreadyVoted {},
+    );
+    assert_eq!(
+        b_app.query_tally(0)?.tally,
+        vec![("approve".to_string(), 1), ("reject".to_string(), 2)]
     );
 
-    // vote on the proposal
-    let vote_response = app.vote("approve".to_string(), 0);
+    // votes are only accepted from the remote voting chains of the proposal
+    let vote_response = deliver_module_ibc(
+        &b_app,
+        B_CHAIN_ID,
+        &CCGovIbcMessage::RemoteVote {
+            prop_id: 0,
+            voter: b.addr_make("voter2").to_string(),
+            options: vec![("approve".to_string(), Decimal::one())],
+        },
+    );
+    assert_contract_err(vote_response, ContractError::UnauthorizedIbcMessage {});
 
-    // print the vote response
-    println!("{:?}", vote_response);
+    b.wait_seconds(60)?;
+    b_app.execute_proposal(0)?;
+    assert_eq!(b_app.query_tally(0)?.outcome, Some("reject".to_string()));
 
-    // ensure the vote was successful
-    assert!(vote_response.is_ok(), "{:?}", vote_response);
+    // the proposal is not closed early while the total power of a remote chain is unknown
+    let create_response = remote_voting_proposal.clone().create(&b_app)?;
+    for index in 0..10 {
+        let voter = b.addr_make(format!("local_voter{index}"));
+        b_app.call_as(&voter).vote("approve".to_string(), 1)?;
+    }
+    assert_contract_err(
+        b_app.execute_proposal(1),
+        ContractError::VotingPeriodNotEnded {},
+    );
+    interchain.check_ibc(B_CHAIN_ID, create_response)?;
+    assert_eq!(b_app.query_tally(1)?.total_power, 110);
+
+    // the total power of a proposal that starts later is queried on execution
+    let start_time = b.block_info()?.time.plus_seconds(30);
+    let create_response = TestProposal {
+        start_time: Some(start_time),
+        ..remote_voting_proposal
+    }
+    .create(&b_app)?;
+    interchain.check_ibc(B_CHAIN_ID, create_response)?;
+    assert_eq!(b_app.query_tally(2)?.total_power, 10);
+    b.wait_seconds(90)?;
+    let execute_response = b_app.execute_proposal(2)?;
+    assert!(execute_response
+        .has_event(&Event::new("wasm").add_attribute("result", "remote_total_power_unknown")));
+    interchain.check_ibc(B_CHAIN_ID, execute_response)?;
+    assert_eq!(b_app.query_tally(2)?.total_power, 110);
+    b_app.execute_proposal(2)?;
+    assert!(b_app.query_tally(2)?.outcome.is_some());
 
-    // query the vote
-    let query_vote_response = app.query_vote(0, sender.clone().to_string())?;
+    Ok(())
+}
 
-    // ensure the vote was created correctly
-    assert_eq!(query_vote_response.vote.id, 0);
-    assert_eq!(query_vote_response.vote.prop_id, 0);
-    assert_eq!(query_vote_response.vote.voter, sender.clone());
-    assert_eq!(query_vote_response.vote.power, 1);
-    assert_eq!(query_vote_response.vote.option, "approve");
+#[test]
+fn auto_execute_test() -> anyhow::Result<()> {
+    let interchain =
+        MockBech32InterchainEnv::new(vec![(A_CHAIN_ID, A_SENDER), (B_CHAIN_ID, B_SENDER)]);
 
-    // check the total power of the proposal is 1
-    let query_total_power_response = app.query_total_voted_power(0)?;
+    let a = interchain.chain(A_CHAIN_ID)?;
+    let b = interchain.chain(B_CHAIN_ID)?;
 
-    // ensure the total power is correct
-    assert_eq!(query_total_power_response.power, 1);
+    let a_env = TestEnv::setup(a.clone())?;
+    let b_env = TestEnv::setup_with_msg(
+        b.clone(),
+        CCGovInstantiateMsg {
+            auto_execute: true,
+            ..instantiate_msg(&b.sender())
+        },
+    )?;
 
-    // try to execute the proposal
-    let execute_proposal_response = app.execute_proposal(0);
+    a_env.enable_ibc()?;
+    b_env.enable_ibc()?;
 
-    // ensure the proposal was not executed
-    assert!(execute_proposal_response.is_err());
+    ibc_connect_polytone_and_abstract(&interchain, B_CHAIN_ID, A_CHAIN_ID)?;
+    ibc_connect_polytone_and_abstract(&interchain, A_CHAIN_ID, B_CHAIN_ID)?;
 
-    // advance the block time by 1 minute
-    app.get_chain().wait_seconds(60);
+    let a_app = a_env.app;
+    let b_app = b_env.app;
 
-    // try to execute the proposal again
-    let execute_proposal_response2 = app.execute_proposal(0);
+    let a_fixed_power_addr = instantiate_fixed_power(&a, 10);
+    let b_fixed_power_addr = instantiate_fixed_power(&b, 10);
+    a_app.add_power_contract(a_fixed_power_addr.to_string())?;
+    b_app.add_power_contract(b_fixed_power_addr.to_string())?;
+    b_app.add_trusted_instance(
+        ChainName::from_chain_id(A_CHAIN_ID).to_string(),
+        a_app.as_instance().address()?.to_string(),
+    )?;
+
+    TestProposal::new(&a_fixed_power_addr).create(&a_app)?;
+    TestProposal {
+        prereq_proposals: vec![PrerequisiteProposal::new(
+            0,
+            ChainName::from_chain_id(A_CHAIN_ID).to_string(),
+            a_app.as_instance().address()?.to_string(),
+        )],
+        ..TestProposal::new(&b_fixed_power_addr)
+    }
+    .create(&b_app)?;
 
-    // ensure the proposal was executed
-    assert!(
-        execute_proposal_response2.is_ok(),
-        "{:?}",
-        execute_proposal_response2
-    );
+    a_app.vote("approve".to_string(), 0)?;
+    b_app.vote("approve".to_string(), 0)?;
+    a.wait_seconds(60)?;
+    b.wait_seconds(60)?;
 
-    // check that the proposal is in the executed proposals
-    let query_executed_proposals_response = app.query_executed_proposals()?;
+    // the result of chain A is not known yet, so the proposal on chain B waits for it
+    a_app.execute_proposal(0)?;
+    let execute_response = b_app.execute_proposal(0)?;
+    assert_eq!(
+        b_app.query_proposal(0)?.prop.status,
+        ProposalStatus::PendingRemote
+    );
 
-    // ensure the executed proposals are correct
+    // the callback with the result executes the proposal without another ExecuteProposal
+    interchain.check_ibc(B_CHAIN_ID, execute_response)?;
+    assert_eq!(b_app.query_proposal(0)?.prop.status, ProposalStatus::Passed);
     assert_eq!(
-        query_executed_proposals_response.executed_proposals,
+        b_app.query_executed_proposals()?.executed_proposals,
         vec![(0, "approve".to_string())]
     );
 
+    // a failing execution keeps the result, and the proposal can be executed later
+    let b_app_addr = b_app.as_instance().address()?;
+    let approve = ProposalOption {
+        name: "approve".to_string(),
+        msgs: vec![BankMsg::Send {
+            to_address: b.addr_make("recipient").to_string(),
+            amount: coins(40, "ucosm"),
+        }
+        .into()],
+        kind: OptionKind::Standard,
+    };
+    TestProposal {
+        options: vec![approve, "reject".into()],
+        prereq_proposals: vec![PrerequisiteProposal::new(
+            0,
+            ChainName::from_chain_id(A_CHAIN_ID).to_string(),
+            a_app.as_instance().address()?.to_string(),
+        )],
+        ..TestProposal::new(&b_fixed_power_addr)
+    }
+    .create(&b_app)?;
+    b_app.vote("approve".to_string(), 1)?;
+    b.wait_seconds(60)?;
+    let execute_response = b_app.execute_proposal(1)?;
+    interchain.check_ibc(B_CHAIN_ID, execute_response)?;
+    assert!(b_app.query_prerequisites(1)?.prerequisites[0].resolved);
+    assert!(b_app
+        .query_executed_proposals()?
+        .executed_proposals
+        .iter()
+        .all(|(prop_id, _)| *prop_id != 1));
+
+    b.set_balance(&b_app_addr, coins(100, "ucosm"))?;
+    b_app.execute_proposal(1)?;
+    assert_eq!(
+        b_app.query_proposal(1)?.prop.status,
+        ProposalStatus::Executed
+    );
+
+    Ok(())
+}
+
+#[test]
+fn batched_remote_queries_test() -> anyhow::Result<()> {
+    let interchain =
+        MockBech32InterchainEnv::new(vec![(A_CHAIN_ID, A_SENDER), (B_CHAIN_ID, B_SENDER)]);
+
+    let a = interchain.chain(A_CHAIN_ID)?;
+    let b = interchain.chain(B_CHAIN_ID)?;
+
+    let a_env = TestEnv::setup(a.clone())?;
+    let b_env = TestEnv::setup(b.clone())?;
+
+    a_env.enable_ibc()?;
+    b_env.enable_ibc()?;
+
+    ibc_connect_polytone_and_abstract(&interchain, B_CHAIN_ID, A_CHAIN_ID)?;
+    ibc_connect_polytone_and_abstract(&interchain, A_CHAIN_ID, B_CHAIN_ID)?;
+
+    let a_app = a_env.app;
+    let b_app = b_env.app;
+
+    let a_fixed_power_addr = instantiate_fixed_power(&a, 10);
+    let b_fixed_power_addr = instantiate_fixed_power(&b, 10);
+    a_app.add_power_contract(a_fixed_power_addr.to_string())?;
+    b_app.add_power_contract(b_fixed_power_addr.to_string())?;
+
+    // two proposals on chain A that are both prerequisites of the proposal on chain B
+    for _ in 0..2 {
+        TestProposal::new(&a_fixed_power_addr).create(&a_app)?;
+    }
+    let a_chain = ChainName::from_chain_id(A_CHAIN_ID).to_string();
+    let a_addr = a_app.as_instance().address()?.to_string();
+    b_app.add_trusted_instance(a_chain.clone(), a_addr.clone())?;
+    TestProposal {
+        prereq_proposals: vec![
+            PrerequisiteProposal::new(0, a_chain.clone(), a_addr.clone()),
+            PrerequisiteProposal::new(1, a_chain, a_addr),
+        ],
+        ..TestProposal::new(&b_fixed_power_addr)
+    }
+    .create(&b_app)?;
+
+    a_app.vote("approve".to_string(), 0)?;
+    a_app.vote("reject".to_string(), 1)?;
+    b_app.vote("approve".to_string(), 0)?;
+    a.wait_seconds(60)?;
+    b.wait_seconds(60)?;
+    a_app.execute_proposal(0)?;
+    a_app.execute_proposal(1)?;
+
+    // a single execution requests the results of both prerequisites
+    let execute_response = b_app.execute_proposal(0)?;
+    assert_eq!(
+        execute_response.event_attr_value("wasm-remote_proposal_unresolved", "prereq_prop_ids")?,
+        "0,1"
+    );
+    interchain.check_ibc(B_CHAIN_ID, execute_response)?;
+    assert_eq!(
+        b_app.query_proposal(0)?.prop.status,
+        ProposalStatus::PendingExecution
+    );
+
+    b_app.execute_proposal(0)?;
+    assert_eq!(
+        b_app.query_tally(0)?.tally,
+        vec![("approve".to_string(), 2), ("reject".to_string(), 1)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn validate_prereqs_test() -> anyhow::Result<()> {
+    let interchain =
+        MockBech32InterchainEnv::new(vec![(A_CHAIN_ID, A_SENDER), (B_CHAIN_ID, B_SENDER)]);
+
+    let a = interchain.chain(A_CHAIN_ID)?;
+    let b = interchain.chain(B_CHAIN_ID)?;
+
+    let a_env = TestEnv::setup(a.clone())?;
+    let b_env = TestEnv::setup(b.clone())?;
+
+    a_env.enable_ibc()?;
+    b_env.enable_ibc()?;
+
+    ibc_connect_polytone_and_abstract(&interchain, B_CHAIN_ID, A_CHAIN_ID)?;
+    ibc_connect_polytone_and_abstract(&interchain, A_CHAIN_ID, B_CHAIN_ID)?;
+
+    let a_app = a_env.app;
+    let b_app = b_env.app;
+
+    let a_fixed_power_addr = instantiate_fixed_power(&a, 10);
+    let b_fixed_power_addr = instantiate_fixed_power(&b, 10);
+    a_app.add_power_contract(a_fixed_power_addr.to_string())?;
+    b_app.add_power_contract(b_fixed_power_addr.to_string())?;
+
+    // two proposals on chain A, only the first has the options of the proposals on chain B
+    for options in [["approve", "reject"], ["yes", "no"]] {
+        TestProposal {
+            options: options.map(Into::into).to_vec(),
+            ..TestProposal::new(&a_fixed_power_addr)
+        }
+        .create(&a_app)?;
+    }
+
+    // prerequisites on chain A: a matching proposal, one that does not exist, and one with
+    // other options
+    let a_chain = ChainName::from_chain_id(A_CHAIN_ID).to_string();
+    let a_addr = a_app.as_instance().address()?.to_string();
+    b_app.add_trusted_instance(a_chain.clone(), a_addr.clone())?;
+    for remote_prop_id in [0, 7, 1] {
+        let create_response = TestProposal {
+            prereq_proposals: vec![PrerequisiteProposal::new(
+                remote_prop_id,
+                a_chain.clone(),
+                a_addr.clone(),
+            )],
+            validate_prereqs: Some(true),
+            ..TestProposal::new(&b_fixed_power_addr)
+        }
+        .create(&b_app)?;
+        // the query of a missing proposal fails on chain A, so the acks are not checked
+        let _ = interchain.wait_ibc(B_CHAIN_ID, create_response)?;
+    }
+
+    let expected = [
+        (PrerequisiteValidation::Valid, ProposalStatus::Open),
+        (PrerequisiteValidation::NotFound, ProposalStatus::Cancelled),
+        (
+            PrerequisiteValidation::IncompatibleOptions,
+            ProposalStatus::Cancelled,
+        ),
+    ];
+    for (prop_id, (validation, status)) in expected.into_iter().enumerate() {
+        let prerequisites = b_app.query_prerequisites(prop_id as u64)?.prerequisites;
+        assert_eq!(prerequisites[0].validation, Some(validation));
+        assert!(!prerequisites[0].resolved);
+        assert_eq!(b_app.query_proposal(prop_id as u64)?.prop.status, status);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn aggregation_strategy_test() -> anyhow::Result<()> {
+    let interchain =
+        MockBech32InterchainEnv::new(vec![(A_CHAIN_ID, A_SENDER), (B_CHAIN_ID, B_SENDER)]);
+
+    let a = interchain.chain(A_CHAIN_ID)?;
+    let b = interchain.chain(B_CHAIN_ID)?;
+
+    let a_env = TestEnv::setup(a.clone())?;
+    let b_env = TestEnv::setup_with_msg(
+        b.clone(),
+        CCGovInstantiateMsg {
+            quorum: Decimal::percent(50),
+            ..instantiate_msg(&b.sender())
+        },
+    )?;
+
+    a_env.enable_ibc()?;
+    b_env.enable_ibc()?;
+
+    ibc_connect_polytone_and_abstract(&interchain, B_CHAIN_ID, A_CHAIN_ID)?;
+    ibc_connect_polytone_and_abstract(&interchain, A_CHAIN_ID, B_CHAIN_ID)?;
+
+    let a_app = a_env.app;
+    let app = b_env.app;
+    let fixed_power_addr = instantiate_fixed_power(&b, 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let a_chain = ChainName::from_chain_id(A_CHAIN_ID).to_string();
+    app.add_trusted_module(a_chain.clone(), CCGOV_ID.to_string())?;
+    app.add_trusted_instance(a_chain.clone(), a_app.as_instance().address()?.to_string())?;
+    let remote_tally = |approve: u64, reject: u64, outcome: &str| QueryTallyResponse {
+        tally: vec![
+            ("approve".to_string(), approve),
+            ("reject".to_string(), reject),
+        ],
+        total_power: 10,
+        outcome: Some(outcome.to_string()),
+        sources: vec![],
+    };
+
+    // strategy, local approve votes, result of the remote proposal, expected outcome
+    let cases = [
+        (
+            AggregationStrategy::Sum,
+            6,
+            remote_tally(0, 9, "reject"),
+            "reject",
+        ),
+        (
+            AggregationStrategy::WeightedSum {
+                weights: vec![(a_chain.clone(), Decimal::percent(50))],
+            },
+            6,
+            remote_tally(0, 9, "reject"),
+            "approve",
+        ),
+        (
+            AggregationStrategy::RemoteVeto,
+            6,
+            remote_tally(0, 9, VETOED_OUTCOME),
+            VETOED_OUTCOME,
+        ),
+        (
+            AggregationStrategy::AllMustPass,
+            6,
+            remote_tally(0, 9, "reject"),
+            THRESHOLD_NOT_REACHED_OUTCOME,
+        ),
+        (
+            AggregationStrategy::LocalOnlyWithRemoteQuorum,
+            1,
+            remote_tally(0, 9, "reject"),
+            "approve",
+        ),
+    ];
+    for (prop_id, (strategy, approve_votes, remote_result, _)) in cases.iter().enumerate() {
+        let prop_id = prop_id as u64;
+        TestProposal {
+            prereq_proposals: vec![PrerequisiteProposal::new(
+                0,
+                a_chain.clone(),
+                a_app.as_instance().address()?.to_string(),
+            )],
+            aggregation_strategy: Some(strategy.clone()),
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app)?;
+        for voter in 0..*approve_votes {
+            let voter = b.addr_make(format!("voter{voter}"));
+            app.call_as(&voter).vote("approve".to_string(), prop_id)?;
+        }
+
+        // the result of the remote proposal is pushed by chain A
+        deliver_module_ibc(
+            &app,
+            A_CHAIN_ID,
+            &CCGovIbcMessage::ProposalResult {
+                prop_id: 0,
+                parent_prop_id: prop_id,
+                remote_prop_id: prop_id,
+                tally: remote_result.clone(),
+                contract_addr: a_app.as_instance().address()?.to_string(),
+            },
+        )?;
+    }
+
+    b.wait_seconds(60)?;
+
+    for (prop_id, (strategy, _, _, expected)) in cases.iter().enumerate() {
+        app.execute_proposal(prop_id as u64)?;
+        assert_eq!(
+            app.query_tally(prop_id as u64)?.outcome.as_deref(),
+            Some(*expected),
+            "{:?}",
+            strategy
+        );
+    }
+
+    // the tally reports the local votes and the remote result separately
+    let tally = app.query_tally(1)?;
+    assert_eq!(
+        tally.tally,
+        vec![("approve".to_string(), 6), ("reject".to_string(), 4)]
+    );
+    assert_eq!(tally.total_power, 15);
+    assert_eq!(tally.sources.len(), 2);
+    assert_eq!(tally.sources[0].chain, None);
+    assert_eq!(tally.sources[0].total_power, 10);
+    assert_eq!(tally.sources[1].chain, Some(a_chain.clone()));
+    assert_eq!(tally.sources[1].outcome, Some("reject".to_string()));
+
+    // weights need to be positive and given once for the chain of a prerequisite
+    let weighted = |weights: Vec<(String, Decimal)>| -> anyhow::Result<_> {
+        Ok(TestProposal {
+            prereq_proposals: vec![PrerequisiteProposal::new(
+                0,
+                a_chain.clone(),
+                a_app.as_instance().address()?.to_string(),
+            )],
+            aggregation_strategy: Some(AggregationStrategy::WeightedSum { weights }),
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app))
+    };
+    for weights in [
+        vec![(a_chain.clone(), Decimal::zero())],
+        vec![("other".to_string(), Decimal::one())],
+        vec![
+            (a_chain.clone(), Decimal::one()),
+            (a_chain.clone(), Decimal::one()),
+        ],
+    ] {
+        assert_contract_err(
+            weighted(weights)?,
+            ContractError::InvalidAggregationWeights {},
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn normalization_test() -> anyhow::Result<()> {
+    let interchain =
+        MockBech32InterchainEnv::new(vec![(A_CHAIN_ID, A_SENDER), (B_CHAIN_ID, B_SENDER)]);
+
+    let a = interchain.chain(A_CHAIN_ID)?;
+    let b = interchain.chain(B_CHAIN_ID)?;
+
+    let a_env = TestEnv::setup(a.clone())?;
+    let b_env = TestEnv::setup(b.clone())?;
+
+    a_env.enable_ibc()?;
+    b_env.enable_ibc()?;
+
+    ibc_connect_polytone_and_abstract(&interchain, B_CHAIN_ID, A_CHAIN_ID)?;
+    ibc_connect_polytone_and_abstract(&interchain, A_CHAIN_ID, B_CHAIN_ID)?;
+
+    let a_app = a_env.app;
+    let app = b_env.app;
+    let fixed_power_addr = instantiate_fixed_power(&b, 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let a_chain = ChainName::from_chain_id(A_CHAIN_ID).to_string();
+    app.add_trusted_module(a_chain.clone(), CCGOV_ID.to_string())?;
+    app.add_trusted_instance(a_chain.clone(), a_app.as_instance().address()?.to_string())?;
+    // the remote chain has ten times the total power of the local one
+    let remote_result = QueryTallyResponse {
+        tally: vec![("approve".to_string(), 0), ("reject".to_string(), 50)],
+        total_power: 100,
+        outcome: Some("reject".to_string()),
+        sources: vec![],
+    };
+
+    // normalization, expected remote reject votes and total power, expected outcome
+    let cases = [
+        (Normalization::None, 50, 100, "reject"),
+        (
+            Normalization::Multiplier {
+                factor: Decimal::percent(1),
+            },
+            0,
+            1,
+            "approve",
+        ),
+        (Normalization::FractionOfTotalPower, 5, 10, "reject"),
+        (Normalization::Cap { max_power: 20 }, 10, 20, "reject"),
+    ];
+    for (prop_id, (normalization, _, _, _)) in cases.iter().enumerate() {
+        let prop_id = prop_id as u64;
+        TestProposal {
+            prereq_proposals: vec![PrerequisiteProposal {
+                normalization: normalization.clone(),
+                ..PrerequisiteProposal::new(
+                    0,
+                    a_chain.clone(),
+                    a_app.as_instance().address()?.to_string(),
+                )
+            }],
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app)?;
+        app.vote("approve".to_string(), prop_id)?;
+
+        deliver_module_ibc(
+            &app,
+            A_CHAIN_ID,
+            &CCGovIbcMessage::ProposalResult {
+                prop_id: 0,
+                parent_prop_id: prop_id,
+                remote_prop_id: prop_id,
+                tally: remote_result.clone(),
+                contract_addr: a_app.as_instance().address()?.to_string(),
+            },
+        )?;
+    }
+
+    b.wait_seconds(60)?;
+
+    for (prop_id, (normalization, reject_votes, total_power, expected)) in cases.iter().enumerate()
+    {
+        app.execute_proposal(prop_id as u64)?;
+        let tally = app.query_tally(prop_id as u64)?;
+        assert_eq!(
+            tally.outcome.as_deref(),
+            Some(*expected),
+            "{:?}",
+            normalization
+        );
+        assert_eq!(
+            tally.sources[1].tally,
+            vec![
+                ("approve".to_string(), 0),
+                ("reject".to_string(), *reject_votes)
+            ],
+            "{:?}",
+            normalization
+        );
+        assert_eq!(tally.sources[1].total_power, *total_power);
+        assert_eq!(tally.total_power, 10 + total_power);
+    }
+
+    // normalizations cannot scale remote power to nothing or by too much
+    let normalized = |normalization| -> anyhow::Result<_> {
+        Ok(TestProposal {
+            prereq_proposals: vec![PrerequisiteProposal {
+                normalization,
+                ..PrerequisiteProposal::new(
+                    0,
+                    a_chain.clone(),
+                    a_app.as_instance().address()?.to_string(),
+                )
+            }],
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app))
+    };
+    for normalization in [
+        Normalization::Multiplier {
+            factor: Decimal::zero(),
+        },
+        Normalization::Multiplier {
+            factor: Decimal::MAX,
+        },
+        Normalization::Cap { max_power: 0 },
+    ] {
+        assert_contract_err(
+            normalized(normalization)?,
+            ContractError::InvalidNormalization {
+                max: MAX_MULTIPLIER,
+            },
+        );
+    }
+
+    // remote power that does not fit after normalization fails the tally instead of wrapping
+    let prop_id = cases.len() as u64;
+    normalized(Normalization::Multiplier {
+        factor: Decimal::from_ratio(MAX_MULTIPLIER, 1u64),
+    })??;
+    deliver_module_ibc(
+        &app,
+        A_CHAIN_ID,
+        &CCGovIbcMessage::ProposalResult {
+            prop_id: 0,
+            parent_prop_id: prop_id,
+            remote_prop_id: prop_id,
+            tally: QueryTallyResponse {
+                tally: vec![("approve".to_string(), 0), ("reject".to_string(), u64::MAX)],
+                total_power: u64::MAX,
+                ..remote_result
+            },
+            contract_addr: a_app.as_instance().address()?.to_string(),
+        },
+    )?;
+    b.wait_seconds(60)?;
+    assert_contract_err(
+        app.execute_proposal(prop_id),
+        ContractError::PowerOverflow {},
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fixed_power_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+
+    let env = TestEnv::setup(mock)?;
+
+    env_logger::try_init().ok();
+    let app = env.app;
+
+    let sender: Addr = app.get_chain().sender.clone();
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+    {
+        let app2 = app.get_chain().app.borrow();
+
+        let fixed_power_querymsg = FixedPowerQueryMsg::GetVotingPowerMsg {
+            voter: "voter".to_string(),
+            time: None,
+        };
+
+        let fixed_power_response: Result<GetVotingPowerResponse, _> = app2
+            .wrap()
+            .query_wasm_smart(fixed_power_addr.clone(), &fixed_power_querymsg);
+
+        // print the response
+        println!("{:?}", fixed_power_response);
+
+        assert!(fixed_power_response.is_ok());
+        assert_eq!(
+            fixed_power_response.unwrap(),
+            GetVotingPowerResponse { power: 1 }
+        );
+    }
+    let create_prop_response = TestProposal::new(&fixed_power_addr).create(&app);
+
+    // ensure the proposal was created ok
+    assert!(create_prop_response.is_ok(), "{:?}", create_prop_response);
+
+    // query the proposal
+    let query_response = app.query_proposal(0)?;
+
+    // ensure the proposal was created correctly
+    assert_eq!(query_response.prop.id, 0);
+    assert_eq!(query_response.prop.title, "test");
+    assert_eq!(query_response.prop.description, "cosmwasm is awesome");
+    assert_eq!(
+        query_response.prop.options,
+        vec![
+            ProposalOption::from("approve"),
+            ProposalOption::from("reject")
+        ]
+    );
+    // print the power contract address
+    assert_eq!(
+        query_response.prop.power_contract,
+        fixed_power_addr.to_string()
+    );
+
+    // vote on the proposal
+    let vote_response = app.vote("approve".to_string(), 0);
+
+    // print the vote response
+    println!("{:?}", vote_response);
+
+    // ensure the vote was successful
+    assert!(vote_response.is_ok(), "{:?}", vote_response);
+
+    // votes cannot be changed unless revoting is allowed
+    let vote_response = app.vote("reject".to_string(), 0);
+    assert_contract_err(vote_response, ContractError::AlreadyVoted {});
+
+    // query the vote
+    let query_vote_response = app.query_vote(0, sender.clone().to_string())?;
+
+    // ensure the vote was created correctly
+    assert_eq!(query_vote_response.vote.id, 0);
+    assert_eq!(query_vote_response.vote.prop_id, 0);
+    assert_eq!(query_vote_response.vote.voter, sender.clone());
+    assert_eq!(query_vote_response.vote.power, 1);
+    assert_eq!(
+        query_vote_response.vote.options,
+        vec![("approve".to_string(), Decimal::one())]
+    );
+
+    // check the total power of the proposal is 1
+    let query_total_power_response = app.query_total_voted_power(0)?;
+
+    // ensure the total power is correct
+    assert_eq!(query_total_power_response.power, 1);
+
+    // try to execute the proposal
+    let execute_proposal_response = app.execute_proposal(0);
+
+    // ensure the proposal was not executed
+    assert_contract_err(
+        execute_proposal_response,
+        ContractError::VotingPeriodNotEnded {},
+    );
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Open);
+
+    // advance the block time by 1 minute
+    app.get_chain().wait_seconds(60)?;
+    assert_eq!(
+        app.query_proposal(0)?.prop.status,
+        ProposalStatus::PendingExecution
+    );
+
+    // voting is closed after the voting period
+    let vote_response = app.vote("reject".to_string(), 0);
+    assert_contract_err(vote_response, ContractError::VotingPeriodHasEnded {});
+
+    // try to execute the proposal again
+    let execute_proposal_response2 = app.execute_proposal(0);
+
+    // ensure the proposal was executed
+    assert!(
+        execute_proposal_response2.is_ok(),
+        "{:?}",
+        execute_proposal_response2
+    );
+
+    // check that the proposal is in the executed proposals
+    let query_executed_proposals_response = app.query_executed_proposals()?;
+
+    // ensure the executed proposals are correct
+    assert_eq!(
+        query_executed_proposals_response.executed_proposals,
+        vec![(0, "approve".to_string())]
+    );
+
+    // the approve option has no messages, so the proposal just passed
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Passed);
+
+    // the proposal cannot be executed again
+    let execute_proposal_response3 = app.execute_proposal(0);
+    assert_contract_err(
+        execute_proposal_response3,
+        ContractError::ProposalAlreadyExecuted {},
+    );
+    assert_eq!(
+        app.query_executed_proposals()?.executed_proposals,
+        vec![(0, "approve".to_string())]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn proposal_messages_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    // fund the app so it can pay out the proposal
+    let app_addr = app.as_instance().address()?;
+    app.get_chain()
+        .set_balance(&app_addr, coins(100, "ucosm"))?;
+
+    let recipient = app.get_chain().addr_make("recipient");
+
+    // the approve option pays the recipient, the reject option does nothing
+    let approve = ProposalOption {
+        name: "approve".to_string(),
+        msgs: vec![BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(40, "ucosm"),
+        }
+        .into()],
+        kind: OptionKind::Standard,
+    };
+    TestProposal {
+        title: "spend".to_string(),
+        description: "pay the recipient".to_string(),
+        options: vec![approve, "reject".into()],
+        ..TestProposal::new(&fixed_power_addr)
+    }
+    .create(&app)?;
+
+    app.vote("approve".to_string(), 0)?;
+
+    app.get_chain().wait_seconds(60)?;
+
+    app.execute_proposal(0)?;
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Executed);
+
+    // executing the proposal again does not dispatch the messages again
+    assert_contract_err(
+        app.execute_proposal(0),
+        ContractError::ProposalAlreadyExecuted {},
+    );
+
+    // the messages of the winning option were dispatched
+    assert_eq!(
+        app.get_chain().query_balance(&recipient, "ucosm")?.u128(),
+        40
+    );
+    assert_eq!(
+        app.get_chain().query_balance(&app_addr, "ucosm")?.u128(),
+        60
+    );
+
+    Ok(())
+}
+
+#[test]
+fn config_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+
+    let sender: Addr = app.get_chain().sender.clone();
+
+    // the config is stored as given at instantiation
+    let config_response = app.config()?;
+    assert_eq!(config_response.config.voting_period_in_minutes, 1);
+    assert_eq!(config_response.config.default_power_contract, None);
+    assert_eq!(config_response.config.admin, Some(sender.to_string()));
+
+    // without a default power contract, proposals need to specify one
+    let create_prop_response = TestProposal {
+        power_contract_addr: None,
+        ..TestProposal::new(&sender)
+    }
+    .create(&app);
+    assert_contract_err(create_prop_response, ContractError::NoPowerContract {});
+
+    // the admin can update the config, which is validated like at instantiation
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.update_config(CCGovInstantiateMsg {
+        voting_period_in_minutes: 5,
+        default_power_contract: Some(fixed_power_addr.to_string()),
+        ..instantiate_msg(&sender)
+    })?;
+    let config_response = app.config()?;
+    assert_eq!(config_response.config.voting_period_in_minutes, 5);
+    assert_eq!(
+        config_response.config.default_power_contract,
+        Some(fixed_power_addr.to_string())
+    );
+    TestProposal {
+        power_contract_addr: None,
+        ..TestProposal::new(&sender)
+    }
+    .create(&app)?;
+
+    let update_response = app.update_config(CCGovInstantiateMsg {
+        quorum: Decimal::percent(150),
+        ..instantiate_msg(&sender)
+    });
+    assert_contract_err(
+        update_response,
+        ContractError::InvalidConfig {
+            reason: "quorum, threshold and veto threshold must be at most 1".to_string(),
+        },
+    );
+    let update_response = app.update_config(CCGovInstantiateMsg {
+        voting_period_in_minutes: 90,
+        ..instantiate_msg(&sender)
+    });
+    assert_contract_err(
+        update_response,
+        ContractError::InvalidConfig {
+            reason: "voting period must be between the min and max voting period".to_string(),
+        },
+    );
+
+    // only the admin or governance can update the config
+    let outsider = app.get_chain().addr_make("outsider");
+    let update_response = app
+        .call_as(&outsider)
+        .update_config(instantiate_msg(&outsider));
+    assert_contract_err(update_response, ContractError::Unauthorized {});
+    assert_eq!(app.config()?.config.voting_period_in_minutes, 5);
+
+    Ok(())
+}
+
+#[test]
+fn power_contract_whitelist_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    let other_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+
+    // proposals cannot use a power contract that is not whitelisted
+    let create_prop_response = TestProposal::new(&fixed_power_addr).create(&app);
+    assert_contract_err(
+        create_prop_response,
+        ContractError::PowerContractNotWhitelisted {},
+    );
+
+    // only the admin or governance can whitelist power contracts
+    let outsider = app.get_chain().addr_make("outsider");
+    let add_response = app
+        .call_as(&outsider)
+        .add_power_contract(fixed_power_addr.to_string());
+    assert_contract_err(add_response, ContractError::Unauthorized {});
+
+    app.add_power_contract(fixed_power_addr.to_string())?;
+    assert_eq!(
+        app.list_power_contracts(None, None)?.power_contracts,
+        vec![fixed_power_addr.to_string()]
+    );
+
+    // governance can whitelist a power contract through a proposal
+    let app_addr = app.as_instance().address()?;
+    let approve = ProposalOption {
+        name: "approve".to_string(),
+        msgs: vec![wasm_execute(
+            app_addr,
+            &ExecuteMsg::from(CCGovExecuteMsg::AddPowerContract {
+                power_contract_addr: other_power_addr.to_string(),
+            }),
+            vec![],
+        )?
+        .into()],
+        kind: OptionKind::Standard,
+    };
+    TestProposal {
+        title: "whitelist".to_string(),
+        description: "whitelist another power contract".to_string(),
+        options: vec![approve, "reject".into()],
+        ..TestProposal::new(&fixed_power_addr)
+    }
+    .create(&app)?;
+    app.vote("approve".to_string(), 0)?;
+    app.get_chain().wait_seconds(60)?;
+    app.execute_proposal(0)?;
+
+    let mut expected_power_contracts =
+        vec![fixed_power_addr.to_string(), other_power_addr.to_string()];
+    expected_power_contracts.sort();
+    assert_eq!(
+        app.list_power_contracts(None, None)?.power_contracts,
+        expected_power_contracts
+    );
+
+    // removed power contracts cannot be used anymore
+    app.remove_power_contract(fixed_power_addr.to_string())?;
+    let create_prop_response = TestProposal::new(&fixed_power_addr).create(&app);
+    assert_contract_err(
+        create_prop_response,
+        ContractError::PowerContractNotWhitelisted {},
+    );
+
+    Ok(())
+}
+
+#[test]
+fn trusted_modules_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    TestProposal::new(&fixed_power_addr).create(&app)?;
+    let subscribe = CCGovIbcMessage::Subscribe {
+        prop_id: 0,
+        parent_prop_id: 0,
+        remote_prop_id: 0,
+        contract_addr: app.as_instance().address()?.to_string(),
+    };
+
+    // messages from modules that are not trusted are rejected
+    assert_contract_err(
+        deliver_module_ibc(&app, A_CHAIN_ID, &subscribe),
+        ContractError::UnauthorizedIbcMessage {},
+    );
+
+    // only the admin or governance can trust modules
+    let a_chain = ChainName::from_chain_id(A_CHAIN_ID).to_string();
+    let outsider = app.get_chain().addr_make("outsider");
+    let add_response = app
+        .call_as(&outsider)
+        .add_trusted_module(a_chain.clone(), CCGOV_ID.to_string());
+    assert_contract_err(add_response, ContractError::Unauthorized {});
+
+    app.add_trusted_module(a_chain.clone(), CCGOV_ID.to_string())?;
+    assert_eq!(
+        app.list_trusted_modules(None, None)?.trusted_modules,
+        vec![(a_chain.clone(), CCGOV_ID.to_string())]
+    );
+    deliver_module_ibc(&app, A_CHAIN_ID, &subscribe)?;
+
+    // modules are only trusted on their own chain
+    assert_contract_err(
+        deliver_module_ibc(&app, B_CHAIN_ID, &subscribe),
+        ContractError::UnauthorizedIbcMessage {},
+    );
+
+    // unknown messages are rejected with an error
+    assert_contract_err(
+        deliver_module_ibc(&app, A_CHAIN_ID, &"unknown"),
+        ContractError::UnknownIbcMessage {},
+    );
+
+    // other trusted modules cannot subscribe, push results or relay votes
+    let other_module = "other-ns:other";
+    app.add_trusted_module(a_chain.clone(), other_module.to_string())?;
+    let other_msgs = [
+        subscribe.clone(),
+        CCGovIbcMessage::ProposalResult {
+            prop_id: 0,
+            parent_prop_id: 0,
+            remote_prop_id: 0,
+            tally: app.query_tally(0)?,
+            contract_addr: app.as_instance().address()?.to_string(),
+        },
+        CCGovIbcMessage::RemoteVote {
+            prop_id: 0,
+            voter: app.get_chain().addr_make("voter").to_string(),
+            options: vec![("approve".to_string(), Decimal::one())],
+        },
+    ];
+    for msg in other_msgs.iter() {
+        assert_contract_err(
+            deliver_module_ibc_from(
+                &app,
+                A_CHAIN_ID,
+                ModuleInfo::from_id(other_module, APP_VERSION.into())?,
+                msg,
+            ),
+            ContractError::UnauthorizedIbcMessage {},
+        );
+    }
+    app.remove_trusted_module(a_chain.clone(), other_module.to_string())?;
+
+    app.remove_trusted_module(a_chain, CCGOV_ID.to_string())?;
+    assert!(app
+        .list_trusted_modules(None, None)?
+        .trusted_modules
+        .is_empty());
+    assert_contract_err(
+        deliver_module_ibc(&app, A_CHAIN_ID, &subscribe),
+        ContractError::UnauthorizedIbcMessage {},
+    );
+
+    Ok(())
+}
+
+#[test]
+fn quorum_and_threshold_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+
+    // a fifth of the power needs to vote, and the winning option needs more than half of the votes
+    let msg = CCGovInstantiateMsg {
+        quorum: Decimal::percent(20),
+        threshold: Decimal::percent(50),
+        ..instantiate_msg(&mock.sender())
+    };
+    let env = TestEnv::setup_with_msg(mock, msg)?;
+    let app = env.app;
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let other_voter = app.get_chain().addr_make("other_voter");
+
+    for _ in 0..3 {
+        TestProposal::new(&fixed_power_addr).create(&app)?;
+    }
+
+    // proposal 0: a single vote is not enough to reach the quorum
+    app.vote("approve".to_string(), 0)?;
+
+    // proposal 1: a split vote does not reach the threshold
+    app.vote("approve".to_string(), 1)?;
+    app.call_as(&other_voter).vote("reject".to_string(), 1)?;
+
+    // proposal 2: both voters approve
+    app.vote("approve".to_string(), 2)?;
+    app.call_as(&other_voter).vote("approve".to_string(), 2)?;
+
+    app.get_chain().wait_seconds(60)?;
+
+    for prop_id in 0..3 {
+        app.execute_proposal(prop_id)?;
+    }
+
+    let query_tally_response = app.query_tally(0)?;
+    assert_eq!(query_tally_response.total_power, 10);
+    assert_eq!(
+        query_tally_response.outcome,
+        Some(FAILED_QUORUM_OUTCOME.to_string())
+    );
+    assert_eq!(
+        app.query_tally(1)?.outcome,
+        Some(THRESHOLD_NOT_REACHED_OUTCOME.to_string())
+    );
+    assert_eq!(app.query_tally(2)?.outcome, Some("approve".to_string()));
+
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Failed);
+    assert_eq!(app.query_proposal(1)?.prop.status, ProposalStatus::Rejected);
+    assert_eq!(app.query_proposal(2)?.prop.status, ProposalStatus::Passed);
+
+    assert_eq!(
+        app.query_executed_proposals()?.executed_proposals,
+        vec![
+            (0, FAILED_QUORUM_OUTCOME.to_string()),
+            (1, THRESHOLD_NOT_REACHED_OUTCOME.to_string()),
+            (2, "approve".to_string())
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tie_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    // without a threshold, a tie between the leading options still has no winner
+    TestProposal {
+        options: vec!["approve".into(), "reject".into(), "other".into()],
+        ..TestProposal::new(&fixed_power_addr)
+    }
+    .create(&app)?;
+    app.vote("approve".to_string(), 0)?;
+    app.call_as(&app.get_chain().addr_make("other_voter"))
+        .vote("reject".to_string(), 0)?;
+
+    app.get_chain().wait_seconds(60)?;
+    app.execute_proposal(0)?;
+    assert_eq!(
+        app.query_tally(0)?.outcome,
+        Some(THRESHOLD_NOT_REACHED_OUTCOME.to_string())
+    );
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Rejected);
+
+    Ok(())
+}
+
+#[test]
+fn list_queries_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let voter = app.get_chain().sender();
+    let other_voter = app.get_chain().addr_make("other_voter");
+
+    for _ in 0..3 {
+        TestProposal::new(&fixed_power_addr).create(&app)?;
+    }
+
+    app.vote("approve".to_string(), 0)?;
+    app.call_as(&other_voter).vote("reject".to_string(), 0)?;
+    app.vote("approve".to_string(), 2)?;
+
+    // paginate over the proposals
+    let proposals = app.list_proposals(Some(2), None, None)?.proposals;
+    assert_eq!(proposals.iter().map(|p| p.id).collect::<Vec<_>>(), [0, 1]);
+    let proposals = app.list_proposals(None, Some(1), None)?.proposals;
+    assert_eq!(proposals.iter().map(|p| p.id).collect::<Vec<_>>(), [2]);
+
+    // votes on a proposal, ordered by voter
+    let votes = app.list_votes(0, None, None)?.votes;
+    assert_eq!(votes.len(), 2);
+    let votes = app.list_votes(0, Some(1), None)?.votes;
+    assert_eq!(votes.len(), 1);
+    let votes = app.list_votes(0, None, Some(votes[0].voter.clone()))?.votes;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(app.list_votes(1, None, None)?.votes, vec![]);
+
+    // votes of a voter, ordered by proposal
+    let votes = app
+        .list_votes_by_voter(voter.to_string(), None, None)?
+        .votes;
+    assert_eq!(votes.iter().map(|v| v.prop_id).collect::<Vec<_>>(), [0, 2]);
+    let votes = app
+        .list_votes_by_voter(voter.to_string(), None, Some(0))?
+        .votes;
+    assert_eq!(votes.iter().map(|v| v.prop_id).collect::<Vec<_>>(), [2]);
+    let votes = app
+        .list_votes_by_voter(other_voter.to_string(), None, None)?
+        .votes;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(
+        votes[0].options,
+        vec![("reject".to_string(), Decimal::one())]
+    );
+
+    // filter by the current status
+    app.get_chain().wait_seconds(60)?;
+    app.execute_proposal(0)?;
+    let proposals = app
+        .list_proposals(None, None, Some(ProposalStatus::PendingExecution))?
+        .proposals;
+    assert_eq!(proposals.iter().map(|p| p.id).collect::<Vec<_>>(), [1, 2]);
+    let proposals = app
+        .list_proposals(None, None, Some(ProposalStatus::Open))?
+        .proposals;
+    assert!(proposals.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn power_snapshot_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+
+    let snapshot_power_addr = instantiate_snapshot_power(app.get_chain());
+    app.add_power_contract(snapshot_power_addr.to_string())?;
+
+    TestProposal::new(&snapshot_power_addr).create(&app)?;
+    let start_time = app.query_proposal(0)?.prop.start_time;
+
+    // voting later in the voting period still uses the power at the start of the proposal
+    app.get_chain().wait_seconds(30)?;
+    app.vote("approve".to_string(), 0)?;
+
+    let vote = app
+        .query_vote(0, app.get_chain().sender().to_string())?
+        .vote;
+    assert_eq!(vote.power, start_time.seconds());
+
+    // the total power is also taken at the start of the proposal
+    app.get_chain().wait_seconds(30)?;
+    app.execute_proposal(0)?;
+    assert_eq!(app.query_tally(0)?.total_power, start_time.seconds());
+
+    Ok(())
+}
+
+#[test]
+fn revoting_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let msg = CCGovInstantiateMsg {
+        allow_revoting: true,
+        ..instantiate_msg(&mock.sender())
+    };
+    let env = TestEnv::setup_with_msg(mock, msg)?;
+    let app = env.app;
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    TestProposal::new(&fixed_power_addr).create(&app)?;
+
+    app.vote("approve".to_string(), 0)?;
+    let vote_response = app.vote("reject".to_string(), 0)?;
+    assert!(
+        vote_response.has_event(
+            &Event::new("wasm-vote_changed")
+                .add_attribute("previous_options", "approve:1")
+                .add_attribute("options", "reject:1")
+        ),
+        "{:?}",
+        vote_response
+    );
+
+    // the previous vote is replaced, so it is only counted once
+    let vote = app
+        .query_vote(0, app.get_chain().sender().to_string())?
+        .vote;
+    assert_eq!(vote.id, 0);
+    assert_eq!(vote.options, vec![("reject".to_string(), Decimal::one())]);
+    assert_eq!(app.query_total_voted_power(0)?.power, 1);
+    assert_eq!(
+        app.query_tally(0)?.tally,
+        vec![("approve".to_string(), 0), ("reject".to_string(), 1)]
+    );
+
+    // votes cannot be changed once the voting period has ended
+    app.get_chain().wait_seconds(60)?;
+    assert_contract_err(
+        app.vote("approve".to_string(), 0),
+        ContractError::VotingPeriodHasEnded {},
+    );
+
+    Ok(())
+}
+
+#[test]
+fn abstain_and_veto_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let msg = CCGovInstantiateMsg {
+        quorum: Decimal::percent(20),
+        threshold: Decimal::percent(50),
+        veto_threshold: Decimal::percent(25),
+        ..instantiate_msg(&mock.sender())
+    };
+    let env = TestEnv::setup_with_msg(mock, msg)?;
+    let app = env.app;
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let voter_a = app.get_chain().addr_make("voter_a");
+    let voter_b = app.get_chain().addr_make("voter_b");
+
+    let abstain = ProposalOption {
+        kind: OptionKind::Abstain,
+        ..ProposalOption::from("abstain")
+    };
+    let veto = ProposalOption {
+        kind: OptionKind::NoWithVeto,
+        ..ProposalOption::from("veto")
+    };
+    let options = vec!["approve".into(), "reject".into(), abstain.clone(), veto];
+
+    // a proposal can only have one abstain option
+    let create_prop_response = TestProposal {
+        options: vec!["approve".into(), abstain.clone(), abstain],
+        ..TestProposal::new(&fixed_power_addr)
+    }
+    .create(&app);
+    assert_contract_err(create_prop_response, ContractError::InvalidOption {});
+
+    for _ in 0..3 {
+        TestProposal {
+            options: options.clone(),
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app)?;
+    }
+
+    // proposal 0: abstaining votes count toward the quorum, but not the threshold
+    app.vote("approve".to_string(), 0)?;
+    app.call_as(&voter_a).vote("abstain".to_string(), 0)?;
+    app.call_as(&voter_b).vote("abstain".to_string(), 0)?;
+
+    // proposal 1: a third of the votes are NoWithVeto, above the veto threshold
+    app.vote("approve".to_string(), 1)?;
+    app.call_as(&voter_a).vote("approve".to_string(), 1)?;
+    app.call_as(&voter_b).vote("veto".to_string(), 1)?;
+
+    // proposal 2: only abstaining votes
+    app.vote("abstain".to_string(), 2)?;
+    app.call_as(&voter_a).vote("abstain".to_string(), 2)?;
+
+    app.get_chain().wait_seconds(60)?;
+
+    for prop_id in 0..3 {
+        app.execute_proposal(prop_id)?;
+    }
+
+    assert_eq!(app.query_tally(0)?.outcome, Some("approve".to_string()));
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Passed);
+    assert_eq!(
+        app.query_tally(1)?.outcome,
+        Some(VETOED_OUTCOME.to_string())
+    );
+    assert_eq!(app.query_proposal(1)?.prop.status, ProposalStatus::Rejected);
+    assert_eq!(
+        app.query_tally(2)?.outcome,
+        Some(THRESHOLD_NOT_REACHED_OUTCOME.to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn weighted_vote_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+
+    let snapshot_power_addr = instantiate_snapshot_power(app.get_chain());
+    app.add_power_contract(snapshot_power_addr.to_string())?;
+
+    TestProposal::new(&snapshot_power_addr).create(&app)?;
+    let power = app.query_proposal(0)?.prop.start_time.seconds();
+
+    // weights need to sum to one
+    let weighted_vote_response = app.weighted_vote(
+        vec![
+            ("approve".to_string(), Decimal::percent(70)),
+            ("reject".to_string(), Decimal::percent(20)),
+        ],
+        0,
+    );
+    assert_contract_err(weighted_vote_response, ContractError::InvalidVoteWeights {});
+
+    // weights whose sum overflows are rejected as well
+    let weighted_vote_response = app.weighted_vote(
+        vec![
+            ("approve".to_string(), Decimal::MAX),
+            ("reject".to_string(), Decimal::MAX),
+        ],
+        0,
+    );
+    assert_contract_err(weighted_vote_response, ContractError::InvalidVoteWeights {});
+
+    // options need to be valid and appear only once
+    let weighted_vote_response = app.weighted_vote(
+        vec![
+            ("approve".to_string(), Decimal::percent(50)),
+            ("approve".to_string(), Decimal::percent(50)),
+        ],
+        0,
+    );
+    assert_contract_err(weighted_vote_response, ContractError::InvalidOption {});
+
+    let options = vec![
+        ("approve".to_string(), Decimal::percent(70)),
+        ("reject".to_string(), Decimal::percent(30)),
+    ];
+    app.weighted_vote(options.clone(), 0)?;
+
+    let vote = app
+        .query_vote(0, app.get_chain().sender().to_string())?
+        .vote;
+    assert_eq!(vote.options, options);
+    assert_eq!(vote.power, power);
+
+    // the power is split across the options, rounding down
+    assert_eq!(
+        app.query_tally(0)?.tally,
+        vec![
+            ("approve".to_string(), power * 7 / 10),
+            ("reject".to_string(), power * 3 / 10)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn delegation_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+
+    let fixed_power_addr = instantiate_fixed_power(app.get_chain(), 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let delegate = app.get_chain().sender();
+    let other_delegate = app.get_chain().addr_make("other_delegate");
+    let delegator_a = app.get_chain().addr_make("delegator_a");
+    let delegator_b = app.get_chain().addr_make("delegator_b");
+
+    // delegating to yourself or undelegating without a delegation fails
+    assert_contract_err(
+        app.delegate(delegate.to_string()),
+        ContractError::SelfDelegation {},
+    );
+    assert_contract_err(app.undelegate(), ContractError::NotDelegated {});
+
+    app.call_as(&delegator_a).delegate(delegate.to_string())?;
+    app.call_as(&delegator_b).delegate(delegate.to_string())?;
+    assert_eq!(
+        app.delegation(delegator_a.to_string())?.delegate,
+        Some(delegate.to_string())
+    );
+    assert_eq!(
+        app.list_delegators(delegate.to_string(), None, None)?
+            .delegators
+            .len(),
+        2
+    );
+
+    TestProposal::new(&fixed_power_addr).create(&app)?;
+    assert_eq!(app.delegated_power(delegate.to_string(), 0)?.power, 2);
+
+    // the delegate votes with their own power and the power of both delegators
+    app.vote("approve".to_string(), 0)?;
+    let vote = app.query_vote(0, delegate.to_string())?.vote;
+    assert_eq!(vote.power, 3);
+    assert_eq!(app.delegated_power(delegate.to_string(), 0)?.power, 0);
+
+    // a delegator overrides the vote of the delegate by voting directly
+    let vote_response = app.call_as(&delegator_a).vote("reject".to_string(), 0)?;
+    assert!(vote_response.has_event(&Event::new("wasm-delegate_vote_overridden")));
+    assert_eq!(app.query_vote(0, delegate.to_string())?.vote.power, 2);
+
+    // re-delegating does not count the power of a delegator twice
+    app.call_as(&delegator_b).undelegate()?;
+    app.call_as(&delegator_b)
+        .delegate(other_delegate.to_string())?;
+    assert_eq!(
+        app.list_delegators(delegate.to_string(), None, None)?
+            .delegators,
+        vec![delegator_a.to_string()]
+    );
+    app.call_as(&other_delegate).vote("reject".to_string(), 0)?;
+    assert_eq!(app.query_vote(0, other_delegate.to_string())?.vote.power, 1);
+
+    assert_eq!(
+        app.query_tally(0)?.tally,
+        vec![("approve".to_string(), 2), ("reject".to_string(), 2)]
+    );
+
+    // a delegate can only have a bounded number of delegators
+    for index in 1..MAX_DELEGATORS {
+        let delegator = app.get_chain().addr_make(format!("delegator_{index}"));
+        app.call_as(&delegator).delegate(delegate.to_string())?;
+    }
+    let delegator = app.get_chain().addr_make("one_too_many");
+    assert_contract_err(
+        app.call_as(&delegator).delegate(delegate.to_string()),
+        ContractError::TooManyDelegators {
+            max: MAX_DELEGATORS,
+        },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn deposit_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let community = mock.addr_make("community");
+    let msg = CCGovInstantiateMsg {
+        quorum: Decimal::percent(20),
+        min_deposit: coins(100, "ucosm"),
+        deposit_period_in_minutes: 10,
+        deposit_recipient: Some(community.to_string()),
+        ..instantiate_msg(&mock.sender())
+    };
+    let env = TestEnv::setup_with_msg(mock, msg)?;
+    let app = env.app;
+    let chain = app.get_chain().clone();
+
+    let fixed_power_addr = instantiate_fixed_power(&chain, 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let proposer = chain.sender();
+    let depositor = chain.addr_make("depositor");
+    let voter = chain.addr_make("voter");
+    chain.set_balance(&proposer, coins(1000, "ucosm"))?;
+    chain.set_balance(&depositor, coins(1000, "ucosm"))?;
+
+    let create_proposal = |deposit: &[Coin]| {
+        TestProposal {
+            deposit: deposit.to_vec(),
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app)
+    };
+
+    // only denoms of the minimum deposit are accepted
+    chain.add_balance(&proposer, coins(10, "uother"))?;
+    assert_contract_err(
+        create_proposal(&coins(10, "uother")),
+        ContractError::InvalidDeposit {},
+    );
+
+    // proposal 0 is funded right away, so voting starts
+    create_proposal(&coins(100, "ucosm"))?;
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Open);
+
+    // proposal 1 needs a top up before voting starts
+    create_proposal(&coins(40, "ucosm"))?;
+    assert_eq!(
+        app.query_proposal(1)?.prop.status,
+        ProposalStatus::DepositPeriod
+    );
+    assert_contract_err(
+        app.vote("approve".to_string(), 1),
+        ContractError::VotingNotStarted {},
+    );
+    app.call_as(&depositor).deposit(1, &coins(60, "ucosm"))?;
+    assert_eq!(app.query_proposal(1)?.prop.status, ProposalStatus::Open);
+    assert_contract_err(
+        app.deposit(1, &coins(10, "ucosm")),
+        ContractError::NotInDepositPeriod {},
+    );
+
+    // proposal 2 never reaches the minimum deposit
+    create_proposal(&coins(30, "ucosm"))?;
+
+    // proposal 0 fails the quorum, proposal 1 passes
+    app.vote("approve".to_string(), 1)?;
+    app.call_as(&voter).vote("approve".to_string(), 1)?;
+
+    chain.wait_seconds(60)?;
+    app.execute_proposal(0)?;
+    app.execute_proposal(1)?;
+    assert_contract_err(
+        app.execute_proposal(2),
+        ContractError::DepositPeriodNotEnded {},
+    );
+
+    // the deposit of the proposal that failed the quorum goes to the community address,
+    // the deposits of the passed proposal are refunded
+    assert_eq!(chain.query_balance(&community, "ucosm")?.u128(), 100);
+    assert_eq!(chain.query_balance(&depositor, "ucosm")?.u128(), 1000);
+    assert_eq!(chain.query_balance(&proposer, "ucosm")?.u128(), 870);
+
+    // the deposit of a proposal that did not reach the minimum deposit is refunded
+    chain.wait_seconds(600)?;
+    app.execute_proposal(2)?;
+    assert_eq!(app.query_proposal(2)?.prop.status, ProposalStatus::Failed);
+    assert_eq!(
+        app.query_tally(2)?.outcome,
+        Some(DEPOSIT_NOT_REACHED_OUTCOME.to_string())
+    );
+    assert_eq!(chain.query_balance(&proposer, "ucosm")?.u128(), 900);
+
+    Ok(())
+}
+
+#[test]
+fn cancel_proposal_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let msg = CCGovInstantiateMsg {
+        min_deposit: coins(100, "ucosm"),
+        deposit_period_in_minutes: 10,
+        ..instantiate_msg(&mock.sender())
+    };
+    let env = TestEnv::setup_with_msg(mock, msg)?;
+    let app = env.app;
+    let chain = app.get_chain().clone();
+
+    let fixed_power_addr = instantiate_fixed_power(&chain, 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let proposer = chain.addr_make("proposer");
+    let voter = chain.addr_make("voter");
+    chain.set_balance(&proposer, coins(200, "ucosm"))?;
+
+    for _ in 0..2 {
+        TestProposal {
+            deposit: coins(100, "ucosm"),
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app.call_as(&proposer))?;
+    }
+    assert_eq!(app.query_proposal(0)?.prop.proposer, proposer.to_string());
+
+    // only the proposer, admin or governance can cancel a proposal
+    assert_contract_err(
+        app.call_as(&voter).cancel_proposal(0),
+        ContractError::Unauthorized {},
+    );
+
+    // the proposer can cancel a proposal without votes, which refunds the deposit
+    app.call_as(&proposer).cancel_proposal(0)?;
+    assert_eq!(
+        app.query_proposal(0)?.prop.status,
+        ProposalStatus::Cancelled
+    );
+    assert_eq!(chain.query_balance(&proposer, "ucosm")?.u128(), 100);
+    assert_contract_err(
+        app.call_as(&proposer).cancel_proposal(0),
+        ContractError::ProposalCancelled {},
+    );
+
+    // once a vote was cast, only the admin or governance can cancel the proposal
+    app.call_as(&voter).vote("approve".to_string(), 1)?;
+    assert_contract_err(
+        app.call_as(&proposer).cancel_proposal(1),
+        ContractError::Unauthorized {},
+    );
+    app.cancel_proposal(1)?;
+    assert_eq!(chain.query_balance(&proposer, "ucosm")?.u128(), 200);
+
+    // cancelled proposals can neither be voted on nor executed
+    assert_contract_err(
+        app.vote("approve".to_string(), 1),
+        ContractError::ProposalCancelled {},
+    );
+    chain.wait_seconds(60)?;
+    assert_contract_err(app.execute_proposal(1), ContractError::ProposalCancelled {});
+    assert_eq!(app.query_tally(1)?.outcome, None);
+
+    Ok(())
+}
+
+#[test]
+fn voting_period_and_start_time_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+    let chain = app.get_chain().clone();
+
+    let fixed_power_addr = instantiate_fixed_power(&chain, 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let now = chain.block_info()?.time;
+    let create_proposal = |voting_period: Option<u64>, start_time: Option<Timestamp>| {
+        TestProposal {
+            voting_period_in_minutes: voting_period,
+            start_time,
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app)
+    };
+
+    // the voting period needs to be within the bounds from the config
+    assert_contract_err(
+        create_proposal(Some(0), None),
+        ContractError::InvalidVotingPeriod {},
+    );
+    assert_contract_err(
+        create_proposal(Some(61), None),
+        ContractError::InvalidVotingPeriod {},
+    );
+
+    // the start time cannot be in the past
+    assert_contract_err(
+        create_proposal(None, Some(now.minus_seconds(1))),
+        ContractError::InvalidStartTime {},
+    );
+
+    // a five minute proposal that starts in two minutes
+    create_proposal(Some(5), Some(now.plus_minutes(2)))?;
+    let prop = app.query_proposal(0)?.prop;
+    assert_eq!(prop.start_time, now.plus_minutes(2));
+    assert_eq!(prop.voting_period_in_minutes, Some(5));
+    assert_eq!(prop.expiration, Expiration::AtTime(now.plus_minutes(7)));
+
+    // voting only starts at the start time
+    assert_contract_err(
+        app.vote("approve".to_string(), 0),
+        ContractError::VotingNotStarted {},
+    );
+    chain.wait_seconds(120)?;
+    app.vote("approve".to_string(), 0)?;
+
+    // and ends after the voting period of the proposal
+    chain.wait_seconds(240)?;
+    assert_contract_err(
+        app.execute_proposal(0),
+        ContractError::VotingPeriodNotEnded {},
+    );
+    chain.wait_seconds(60)?;
+    assert_eq!(
+        app.query_proposal(0)?.prop.status,
+        ProposalStatus::PendingExecution
+    );
+    app.execute_proposal(0)?;
+
+    Ok(())
+}
+
+#[test]
+fn expiration_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let env = TestEnv::setup(mock)?;
+    let app = env.app;
+    let chain = app.get_chain().clone();
+
+    let fixed_power_addr = instantiate_fixed_power(&chain, 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    let proposer = chain.addr_make("proposer");
+    let height = chain.block_info()?.height;
+    let create_proposal = |expiration: Expiration, voting_period: Option<u64>| {
+        TestProposal {
+            voting_period_in_minutes: voting_period,
+            expiration: Some(expiration),
+            ..TestProposal::new(&fixed_power_addr)
+        }
+        .create(&app.call_as(&proposer))
+    };
+
+    // an explicit expiration cannot be combined with a voting period or be in the past
+    assert_contract_err(
+        create_proposal(Expiration::AtHeight(height + 10), Some(5)),
+        ContractError::InvalidVotingPeriod {},
+    );
+    assert_contract_err(
+        create_proposal(Expiration::AtHeight(height), None),
+        ContractError::InvalidVotingPeriod {},
+    );
+
+    // proposal 0 ends at a block height, proposal 1 never ends by itself
+    create_proposal(Expiration::AtHeight(height + 10), None)?;
+    create_proposal(Expiration::Never {}, None)?;
+
+    app.vote("approve".to_string(), 0)?;
+    app.vote("approve".to_string(), 1)?;
+
+    // proposals that expire at a height or time cannot be closed manually
+    assert_contract_err(app.close_proposal(0), ContractError::CannotCloseProposal {});
+
+    // time passing does not end the voting period of proposal 0, blocks do
+    chain
+        .app
+        .borrow_mut()
+        .update_block(|block| block.time = block.time.plus_hours(1));
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Open);
+    assert_contract_err(
+        app.execute_proposal(0),
+        ContractError::VotingPeriodNotEnded {},
+    );
+    chain.wait_blocks(10)?;
+    assert_eq!(
+        app.query_proposal(0)?.prop.status,
+        ProposalStatus::PendingExecution
+    );
+    app.execute_proposal(0)?;
+
+    // proposal 1 is still open until the admin closes it, its proposer cannot
+    assert_eq!(app.query_proposal(1)?.prop.status, ProposalStatus::Open);
+    assert_contract_err(
+        app.call_as(&proposer).close_proposal(1),
+        ContractError::Unauthorized {},
+    );
+    app.close_proposal(1)?;
+    assert_contract_err(
+        app.vote("reject".to_string(), 1),
+        ContractError::VotingPeriodHasEnded {},
+    );
+    app.execute_proposal(1)?;
+    assert_eq!(app.query_tally(1)?.outcome, Some("approve".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn early_close_test() -> anyhow::Result<()> {
+    let mock = MockBech32::new("mock");
+    let msg = CCGovInstantiateMsg {
+        threshold: Decimal::percent(50),
+        ..instantiate_msg(&mock.sender())
+    };
+    let env = TestEnv::setup_with_msg(mock, msg)?;
+    let app = env.app;
+    let chain = app.get_chain().clone();
+
+    // each voter has a power of 1 out of 10
+    let fixed_power_addr = instantiate_fixed_power(&chain, 10);
+    app.add_power_contract(fixed_power_addr.to_string())?;
+
+    TestProposal::new(&fixed_power_addr).create(&app)?;
+
+    // with five approvals, the remaining power could still tie the vote
+    for index in 0..5 {
+        let voter = chain.addr_make(format!("voter_{index}"));
+        app.call_as(&voter).vote("approve".to_string(), 0)?;
+    }
+    assert_contract_err(
+        app.execute_proposal(0),
+        ContractError::VotingPeriodNotEnded {},
+    );
+
+    // with six approvals, the remaining power cannot change the outcome anymore
+    let voter = chain.addr_make("voter_5");
+    app.call_as(&voter).vote("approve".to_string(), 0)?;
+    let execute_proposal_response = app.execute_proposal(0)?;
+    assert!(execute_proposal_response.has_event(
+        &Event::new("wasm")
+            .add_attribute("result", "approve")
+            .add_attribute("closed_early", "true")
+    ));
+    assert_eq!(app.query_proposal(0)?.prop.status, ProposalStatus::Passed);
+
+    // votes are closed once the proposal was executed
+    assert_contract_err(
+        app.vote("reject".to_string(), 0),
+        ContractError::VotingPeriodHasEnded {},
+    );
+
     Ok(())
 }